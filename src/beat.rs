@@ -0,0 +1,173 @@
+//! Energy-based beat (onset) detection
+
+use std::collections::VecDeque;
+
+/// Frames of energy history each frame is compared against (about 0.7 s at 60 fps)
+const HISTORY_LEN: usize = 43;
+/// Shortest gap between beats in seconds (caps detection at 240 BPM)
+const MIN_INTERVAL_SECS: f32 = 0.25;
+/// Energy below which nothing counts as a beat
+const MIN_ENERGY: f32 = 0.05;
+/// Inter-beat intervals kept for tempo estimation
+const INTERVAL_HISTORY_LEN: usize = 16;
+/// Intervals needed before a tempo is reported
+const MIN_INTERVALS: usize = 4;
+
+/// Flags frames whose energy jumps above the recent average
+#[derive(Debug, Clone)]
+pub struct BeatDetector {
+    history: VecDeque<f32>,
+    /// Multiple of the average energy a frame must exceed
+    sensitivity: f32,
+    last_beat_time: Option<f32>,
+    beat: bool,
+    /// Seconds between recent consecutive beats, oldest first
+    intervals: VecDeque<f32>,
+}
+
+impl Default for BeatDetector {
+    fn default() -> Self {
+        BeatDetector {
+            history: VecDeque::with_capacity(HISTORY_LEN),
+            sensitivity: 1.4,
+            last_beat_time: None,
+            beat: false,
+            intervals: VecDeque::with_capacity(INTERVAL_HISTORY_LEN),
+        }
+    }
+}
+
+impl BeatDetector {
+    /// Feed the energy (0.0 - 1.0) of the frame at `time` seconds; returns whether it's a beat
+    pub fn update(&mut self, energy: f32, time: f32) -> bool {
+        let average = if self.history.is_empty() {
+            0.0
+        } else {
+            self.history.iter().sum::<f32>() / self.history.len() as f32
+        };
+        // Time jumping backwards (scrubbing) restarts the refractory period
+        let rested = self
+            .last_beat_time
+            .is_none_or(|last| time < last || time - last >= MIN_INTERVAL_SECS);
+        let warmed_up = self.history.len() >= HISTORY_LEN / 2;
+
+        self.beat = warmed_up && rested && energy > MIN_ENERGY && energy > average * self.sensitivity;
+        if self.beat {
+            match self.last_beat_time {
+                // Scrubbing backwards invalidates the tempo history
+                Some(last) if time < last => self.intervals.clear(),
+                Some(last) => {
+                    if self.intervals.len() == INTERVAL_HISTORY_LEN {
+                        self.intervals.pop_front();
+                    }
+                    self.intervals.push_back(time - last);
+                }
+                None => {}
+            }
+            self.last_beat_time = Some(time);
+        }
+
+        if self.history.len() == HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back(energy);
+        self.beat
+    }
+
+    /// Set the multiple of the average energy a frame must exceed to count as a beat
+    pub fn set_sensitivity(&mut self, sensitivity: f32) {
+        self.sensitivity = sensitivity.clamp(1.0, 4.0);
+    }
+
+    /// Whether the most recent frame was a beat
+    pub fn is_beat(&self) -> bool {
+        self.beat
+    }
+
+    /// Position through the current beat at `time` (0.0 on the beat - 1.0), None without a tempo
+    ///
+    /// Runs off the estimated BPM from the last beat, so it stays on the grid through quiet bars.
+    pub fn pulse_phase(&self, time: f32) -> Option<f32> {
+        let bpm = self.get_bpm();
+        let last = self.last_beat_time?;
+        if bpm <= 0.0 || time < last {
+            return None;
+        }
+        Some(((time - last) * bpm / 60.0).fract())
+    }
+
+    /// Estimated tempo from the median inter-beat interval, or 0.0 until enough beats are seen
+    ///
+    /// The median ignores the odd missed or doubled beat that would skew an average.
+    pub fn get_bpm(&self) -> f32 {
+        if self.intervals.len() < MIN_INTERVALS {
+            return 0.0;
+        }
+        let mut sorted: Vec<f32> = self.intervals.iter().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1] + sorted[mid]) * 0.5
+        } else {
+            sorted[mid]
+        };
+        60.0 / median
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    fn warmed_up(energy: f32) -> BeatDetector {
+        let mut detector = BeatDetector::default();
+        for frame in 0..HISTORY_LEN {
+            assert!(!detector.update(energy, frame as f32 * FRAME));
+        }
+        detector
+    }
+
+    #[test]
+    fn steady_energy_is_not_a_beat() {
+        let mut detector = warmed_up(0.3);
+        assert!(!detector.update(0.3, 1.0));
+    }
+
+    #[test]
+    fn spike_is_a_beat_once_per_interval() {
+        let mut detector = warmed_up(0.2);
+        assert!(detector.update(0.8, 1.0));
+        assert!(detector.is_beat());
+        assert!(!detector.update(0.9, 1.0 + FRAME));
+        assert!(detector.update(0.95, 1.0 + MIN_INTERVAL_SECS));
+    }
+
+    #[test]
+    fn bpm_is_median_of_intervals() {
+        let mut detector = warmed_up(0.1);
+        assert_eq!(detector.get_bpm(), 0.0);
+
+        // 120 BPM with one late outlier; quiet frames in between keep the average low
+        let beats = [1.0, 1.5, 2.0, 2.5, 3.4, 3.9, 4.4];
+        for &time in &beats {
+            assert!(detector.update(0.9, time));
+            for frame in 1..10 {
+                detector.update(0.1, time + frame as f32 * FRAME);
+            }
+        }
+        assert!((detector.get_bpm() - 120.0).abs() < 0.1, "{}", detector.get_bpm());
+
+        // Half a beat after the last one, and still on the grid two quiet beats later
+        let last = beats[beats.len() - 1];
+        assert!((detector.pulse_phase(last + 0.25).unwrap() - 0.5).abs() < 0.01);
+        assert!(detector.pulse_phase(last + 1.0).unwrap() < 0.01);
+    }
+
+    #[test]
+    fn silence_is_not_a_beat() {
+        let mut detector = warmed_up(0.0);
+        assert!(!detector.update(MIN_ENERGY * 0.5, 1.0));
+    }
+}
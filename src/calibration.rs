@@ -0,0 +1,107 @@
+//! Warm-up calibration of the auto-gain and beat sensitivity from the opening audio
+
+/// Bass energy below which a calibration frame is treated as silence
+const SILENCE: f32 = 0.01;
+/// Beat sensitivity range the calibration may pick
+const SENSITIVITY_RANGE: (f32, f32) = (1.15, 2.0);
+
+/// Levels measured over a calibration window
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibrationResult {
+    /// Loudest RMS seen, used to seed the auto-gain's running peak
+    pub rms_peak: f32,
+    /// Multiple of the average bass energy a beat must exceed
+    pub beat_sensitivity: f32,
+}
+
+/// Accumulates RMS and bass energy statistics for `duration` seconds
+#[derive(Debug, Clone)]
+pub struct Calibration {
+    remaining: f32,
+    rms_peak: f32,
+    bass_sum: f32,
+    bass_sq_sum: f32,
+    frames: u32,
+}
+
+impl Calibration {
+    pub fn new(duration: f32) -> Self {
+        Calibration {
+            remaining: duration,
+            rms_peak: 0.0,
+            bass_sum: 0.0,
+            bass_sq_sum: 0.0,
+            frames: 0,
+        }
+    }
+
+    /// Add a frame's levels; returns the result once the window has elapsed
+    pub fn update(&mut self, dt: f32, rms: f32, bass: f32) -> Option<CalibrationResult> {
+        self.rms_peak = self.rms_peak.max(rms);
+        if bass > SILENCE {
+            self.bass_sum += bass;
+            self.bass_sq_sum += bass * bass;
+            self.frames += 1;
+        }
+        self.remaining -= dt.max(0.0);
+        (self.remaining <= 0.0).then(|| self.result())
+    }
+
+    /// Sensitivity scales with how much the bass fluctuates (its coefficient of variation):
+    /// punchy material can afford a high threshold, heavily compressed material needs a low one.
+    /// Windows with no audible bass keep the default-like lower bound.
+    fn result(&self) -> CalibrationResult {
+        let beat_sensitivity = if self.frames > 0 {
+            let mean = self.bass_sum / self.frames as f32;
+            let variance = (self.bass_sq_sum / self.frames as f32 - mean * mean).max(0.0);
+            1.0 + 0.8 * variance.sqrt() / mean
+        } else {
+            SENSITIVITY_RANGE.0
+        };
+        CalibrationResult {
+            rms_peak: self.rms_peak,
+            beat_sensitivity: beat_sensitivity.clamp(SENSITIVITY_RANGE.0, SENSITIVITY_RANGE.1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    fn calibrate(levels: impl Fn(u32) -> (f32, f32)) -> CalibrationResult {
+        let mut calibration = Calibration::new(1.0);
+        (0..120)
+            .find_map(|frame| {
+                let (rms, bass) = levels(frame);
+                calibration.update(FRAME, rms, bass)
+            })
+            .expect("calibration finishes within its window")
+    }
+
+    #[test]
+    fn finishes_after_duration() {
+        let mut calibration = Calibration::new(0.5);
+        assert!(calibration.update(0.25, 0.2, 0.3).is_none());
+        assert!(calibration.update(0.25, 0.4, 0.3).is_some());
+    }
+
+    #[test]
+    fn punchy_audio_raises_sensitivity() {
+        let steady = calibrate(|_| (0.3, 0.4));
+        let punchy = calibrate(|frame| if frame % 15 == 0 { (0.6, 0.9) } else { (0.2, 0.1) });
+
+        assert_eq!(steady.beat_sensitivity, SENSITIVITY_RANGE.0);
+        assert!(punchy.beat_sensitivity > steady.beat_sensitivity);
+        assert_eq!(punchy.rms_peak, 0.6);
+    }
+
+    #[test]
+    fn silence_keeps_lower_bound() {
+        let silent = calibrate(|_| (0.0, 0.0));
+        assert_eq!(silent.beat_sensitivity, SENSITIVITY_RANGE.0);
+        assert_eq!(silent.rms_peak, 0.0);
+    }
+}
@@ -0,0 +1,59 @@
+//! Camera effects driven by audio events
+
+/// Decay rate of the shake energy per second (~200ms to settle)
+const SHAKE_DECAY: f32 = 20.0;
+
+/// Amplitude jump above the running average that counts as an impact
+const ONSET_THRESHOLD: f32 = 0.15;
+
+/// Impact-style camera shake: a random kick on each onset that decays exponentially
+#[derive(Debug, Clone)]
+pub struct CameraShake {
+    /// Maximum offset in world units (0.0 disables)
+    pub intensity: f32,
+    energy: f32,
+    direction: [f32; 2],
+    rng_state: u32,
+}
+
+impl Default for CameraShake {
+    fn default() -> Self {
+        CameraShake {
+            intensity: 0.0,
+            energy: 0.0,
+            direction: [0.0, 0.0],
+            rng_state: 0x2545_f491,
+        }
+    }
+}
+
+impl CameraShake {
+    /// Advance by `dt` seconds, kicking if `amplitude` jumps above `average`,
+    /// and return the current camera offset
+    pub fn update(&mut self, dt: f32, amplitude: f32, average: f32) -> [f32; 2] {
+        if self.intensity <= 0.0 {
+            self.energy = 0.0;
+            return [0.0, 0.0];
+        }
+
+        if amplitude - average > ONSET_THRESHOLD {
+            let angle = xorshift(&mut self.rng_state) * std::f32::consts::TAU;
+            self.direction = [angle.cos(), angle.sin()];
+            self.energy = 1.0;
+        }
+
+        self.energy *= (-SHAKE_DECAY * dt.max(0.0)).exp();
+        let offset = self.energy * self.intensity;
+        [self.direction[0] * offset, self.direction[1] * offset]
+    }
+}
+
+/// Xorshift random number in 0.0 - 1.0, advancing `state` (which must be non-zero)
+pub fn xorshift(state: &mut u32) -> f32 {
+    let mut x = *state;
+    x ^= x << 13;
+    x ^= x >> 17;
+    x ^= x << 5;
+    *state = x;
+    x as f32 / u32::MAX as f32
+}
@@ -0,0 +1,89 @@
+//! Structured errors, surfaced to JavaScript as `Error` objects with a `code`
+
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// Errors returned by the visualizer
+#[derive(Debug, Clone, PartialEq)]
+pub enum OscError {
+    /// The canvas id doesn't exist or isn't a canvas
+    CanvasNotFound(String),
+    /// A browser API (window, document, timers) is unavailable
+    Environment(String),
+    /// No suitable GPU adapter was found
+    AdapterUnavailable,
+    /// The adapter refused to create a GPU device
+    DeviceRequestFailed(String),
+    /// The GPU device was lost
+    DeviceLost(String),
+    /// The surface could not be created, configured or acquired
+    SurfaceError(String),
+    /// The GPU ran out of memory
+    OutOfMemory,
+    /// A shader or pipeline failed validation
+    ShaderError(String),
+    /// An argument was out of range or malformed
+    InvalidParam(String),
+    /// The feature isn't available with the active renderer or GPU
+    Unsupported(String),
+    /// The call isn't valid in the current state
+    InvalidState(String),
+}
+
+impl OscError {
+    /// Stable identifier JavaScript can branch on
+    pub fn code(&self) -> &'static str {
+        match self {
+            OscError::CanvasNotFound(_) => "CANVAS_NOT_FOUND",
+            OscError::Environment(_) => "ENVIRONMENT",
+            OscError::AdapterUnavailable => "ADAPTER_UNAVAILABLE",
+            OscError::DeviceRequestFailed(_) => "DEVICE_REQUEST_FAILED",
+            OscError::DeviceLost(_) => "DEVICE_LOST",
+            OscError::SurfaceError(_) => "SURFACE_ERROR",
+            OscError::OutOfMemory => "OUT_OF_MEMORY",
+            OscError::ShaderError(_) => "SHADER_ERROR",
+            OscError::InvalidParam(_) => "INVALID_PARAM",
+            OscError::Unsupported(_) => "UNSUPPORTED",
+            OscError::InvalidState(_) => "INVALID_STATE",
+        }
+    }
+}
+
+impl fmt::Display for OscError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OscError::AdapterUnavailable => write!(f, "Failed to find suitable GPU adapter"),
+            OscError::OutOfMemory => write!(f, "GPU out of memory"),
+            OscError::CanvasNotFound(message)
+            | OscError::Environment(message)
+            | OscError::DeviceRequestFailed(message)
+            | OscError::DeviceLost(message)
+            | OscError::SurfaceError(message)
+            | OscError::ShaderError(message)
+            | OscError::InvalidParam(message)
+            | OscError::Unsupported(message)
+            | OscError::InvalidState(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for OscError {}
+
+impl From<wgpu::SurfaceError> for OscError {
+    fn from(error: wgpu::SurfaceError) -> Self {
+        match error {
+            wgpu::SurfaceError::OutOfMemory => OscError::OutOfMemory,
+            other => OscError::SurfaceError(format!("Failed to get surface texture: {}", other)),
+        }
+    }
+}
+
+impl From<OscError> for JsValue {
+    /// A JS `Error` carrying `message` and a `code` property
+    fn from(error: OscError) -> Self {
+        let js_error = js_sys::Error::new(&error.to_string());
+        // Setting a property on a fresh Error object can't fail
+        let _ = js_sys::Reflect::set(&js_error, &"code".into(), &error.code().into());
+        js_error.into()
+    }
+}
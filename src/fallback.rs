@@ -0,0 +1,87 @@
+//! Canvas 2D fallback renderer for browsers without WGPU support
+
+use wasm_bindgen::prelude::*;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+use crate::error::OscError;
+use crate::wave::{calculate_wave, WaveParams};
+
+/// Horizontal distance in pixels between wave samples
+const SAMPLE_STEP: u32 = 4;
+
+/// CPU renderer drawing a single wave trace with the 2D canvas context
+pub struct CanvasFallback {
+    canvas: HtmlCanvasElement,
+    context: CanvasRenderingContext2d,
+}
+
+impl CanvasFallback {
+    /// Create a fallback renderer for the given canvas
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    pub fn new(canvas: HtmlCanvasElement) -> Result<Self, OscError> {
+        let context = canvas.get_context("2d")
+            .ok()
+            .flatten()
+            .ok_or_else(|| OscError::Unsupported("No 2D canvas context".to_string()))?
+            .dyn_into::<CanvasRenderingContext2d>()
+            .map_err(|_| OscError::Unsupported("Context is not a 2D canvas context".to_string()))?;
+
+        log::info!("🖌️ Canvas 2D fallback created: {}x{}", canvas.width(), canvas.height());
+
+        Ok(Self { canvas, context })
+    }
+
+    /// Resize the canvas backing store
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), OscError> {
+        if width > 0 && height > 0 {
+            self.canvas.set_width(width);
+            self.canvas.set_height(height);
+        }
+        Ok(())
+    }
+
+    /// Render a frame
+    pub fn render(&mut self, time: f32, params: &WaveParams) -> Result<(), OscError> {
+        let width = self.canvas.width();
+        let height = self.canvas.height();
+        if width == 0 || height == 0 {
+            return Ok(());
+        }
+        let (w, h) = (width as f64, height as f64);
+        let aspect = (w / h) as f32;
+        let ctx = &self.context;
+
+        // Background
+        ctx.set_shadow_blur(0.0);
+        ctx.set_fill_style_str("rgb(5, 5, 13)");
+        ctx.fill_rect(0.0, 0.0, w, h);
+
+        // Wave trace, sampled along the center line like the shader's glow line
+        ctx.begin_path();
+        for px in (0..=width).step_by(SAMPLE_STEP as usize) {
+            let x = (px as f32 / width as f32 * 2.0 - 1.0) * aspect;
+            let wave = calculate_wave(x, 0.0, time, params);
+            // Canvas y grows downwards, so positive amplitudes are drawn above the center
+            let y = h - (wave as f64 * 0.5 + 1.0) * 0.5 * h;
+            if px == 0 {
+                ctx.move_to(px as f64, y);
+            } else {
+                ctx.line_to(px as f64, y);
+            }
+        }
+
+        let color = format!(
+            "hsl({}, {}%, {}%)",
+            params.hue,
+            params.saturation * 100.0,
+            (params.lightness + 0.1).min(1.0) * 100.0
+        );
+        ctx.set_stroke_style_str(&color);
+        ctx.set_shadow_color(&color);
+        ctx.set_shadow_blur(12.0);
+        ctx.set_line_width((params.line_width as f64 * h * 0.25).max(1.0));
+        ctx.stroke();
+
+        Ok(())
+    }
+}
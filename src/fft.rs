@@ -0,0 +1,90 @@
+//! Radix-2 FFT for analysing raw samples without an AnalyserNode
+
+use std::f32::consts::TAU;
+
+/// Floor for magnitudes converted to decibels (avoids -inf on silence)
+pub const MIN_DECIBELS: f32 = -200.0;
+
+/// In-place iterative radix-2 FFT; both slices must share a power-of-two length
+pub fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+    if n < 2 {
+        return;
+    }
+
+    // Bit-reversal permutation
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if j > i {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+    while size <= n {
+        let half = size / 2;
+        let step = -TAU / size as f32;
+        for start in (0..n).step_by(size) {
+            for k in 0..half {
+                let (sin, cos) = (step * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + half);
+                let t_re = re[b] * cos - im[b] * sin;
+                let t_im = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        size *= 2;
+    }
+}
+
+/// Magnitudes in dB of the first `samples.len() / 2` bins, scaled by 1/N like `AnalyserNode`
+pub fn magnitudes_db(samples: &[f32]) -> Vec<f32> {
+    let n = samples.len();
+    let mut re = samples.to_vec();
+    let mut im = vec![0.0; n];
+    fft(&mut re, &mut im);
+
+    re.iter()
+        .zip(&im)
+        .take(n / 2)
+        .map(|(&re, &im)| {
+            let magnitude = (re * re + im * im).sqrt() / n as f32;
+            (20.0 * magnitude.log10()).max(MIN_DECIBELS)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sine_peaks_at_its_bin() {
+        let n = 256;
+        let samples: Vec<f32> = (0..n).map(|i| (TAU * 8.0 * i as f32 / n as f32).sin()).collect();
+        let db = magnitudes_db(&samples);
+
+        assert_eq!(db.len(), n / 2);
+        let peak = db
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map(|(bin, _)| bin);
+        assert_eq!(peak, Some(8));
+        // A unit sine splits its energy between +/- bins: 0.5 / N * N = -6 dB
+        assert!((db[8] + 6.02).abs() < 0.1);
+        assert!(db[20] < -60.0);
+    }
+
+    #[test]
+    fn silence_is_floored() {
+        let db = magnitudes_db(&[0.0; 64]);
+        assert!(db.iter().all(|&level| level == MIN_DECIBELS));
+    }
+}
@@ -0,0 +1,95 @@
+//! Momentary frequency and speed jumps on sharp spectral transients
+
+use crate::camera::xorshift;
+
+/// Spectral flux above which a frame counts as a transient
+const FLUX_THRESHOLD: f32 = 0.04;
+
+/// Decay rate of the jitter per second (~250ms to settle)
+const JITTER_DECAY: f32 = 12.0;
+
+/// Random frequency/speed offsets kicked by transients that decay exponentially
+#[derive(Debug, Clone)]
+pub struct TransientJitter {
+    /// Largest frequency offset (0.0 disables)
+    pub frequency_amount: f32,
+    /// Largest speed offset (0.0 disables)
+    pub speed_amount: f32,
+    energy: f32,
+    /// Direction of each offset, -1.0 - 1.0
+    offsets: [f32; 2],
+    rng_state: u32,
+}
+
+impl Default for TransientJitter {
+    fn default() -> Self {
+        TransientJitter {
+            frequency_amount: 0.0,
+            speed_amount: 0.0,
+            energy: 0.0,
+            offsets: [0.0, 0.0],
+            rng_state: 0x9e37_79b9,
+        }
+    }
+}
+
+impl TransientJitter {
+    /// Whether either offset is enabled (and spectral flux needs computing)
+    pub fn is_enabled(&self) -> bool {
+        self.frequency_amount > 0.0 || self.speed_amount > 0.0
+    }
+
+    /// Advance by `dt` seconds, kicking if `flux` crosses the transient threshold,
+    /// and return the current (frequency, speed) offsets
+    pub fn update(&mut self, dt: f32, flux: f32) -> (f32, f32) {
+        if !self.is_enabled() {
+            self.energy = 0.0;
+            return (0.0, 0.0);
+        }
+
+        if flux > FLUX_THRESHOLD {
+            let mut direction = || xorshift(&mut self.rng_state) * 2.0 - 1.0;
+            self.offsets = [direction(), direction()];
+            self.energy = 1.0;
+        }
+
+        self.energy *= (-JITTER_DECAY * dt.max(0.0)).exp();
+        (
+            self.offsets[0] * self.energy * self.frequency_amount,
+            self.offsets[1] * self.energy * self.speed_amount,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn transient_kicks_then_decays() {
+        let mut jitter = TransientJitter {
+            frequency_amount: 2.0,
+            speed_amount: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(jitter.update(FRAME, 0.0), (0.0, 0.0));
+
+        let (frequency, speed) = jitter.update(FRAME, 0.5);
+        assert!(frequency.abs() > 0.0 && frequency.abs() <= 2.0);
+        assert!(speed.abs() > 0.0 && speed.abs() <= 1.0);
+
+        let mut later = (0.0, 0.0);
+        for _ in 0..30 {
+            later = jitter.update(FRAME, 0.0);
+        }
+        assert!(later.0.abs() < frequency.abs() * 0.01);
+    }
+
+    #[test]
+    fn disabled_ignores_transients() {
+        let mut jitter = TransientJitter::default();
+        assert_eq!(jitter.update(FRAME, 1.0), (0.0, 0.0));
+    }
+}
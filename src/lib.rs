@@ -2,15 +2,57 @@
 //! 
 //! This library provides a GPU-accelerated audio visualizer using WGPU and WebAssembly.
 
+mod beat;
+mod calibration;
+mod camera;
+mod error;
+mod fallback;
+mod fft;
+mod jitter;
+mod palette;
+mod reactive;
 mod renderer;
 mod wave;
 
 use wasm_bindgen::prelude::*;
 use web_sys::HtmlCanvasElement;
-use std::sync::{Arc, Mutex};
+#[cfg(target_arch = "wasm32")]
+use web_sys::OffscreenCanvas;
+use std::sync::{Arc, Mutex, Once};
 
-pub use renderer::Renderer;
-pub use wave::{WaveMode, WaveParams};
+pub use error::OscError;
+pub use renderer::{Renderer, RendererConfig};
+
+use beat::BeatDetector;
+use calibration::Calibration;
+use camera::CameraShake;
+use jitter::TransientJitter;
+use fallback::CanvasFallback;
+use palette::Palette;
+use reactive::{ReactiveBinding, ReactiveInputs, ReactiveSource, ReactiveTarget};
+use renderer::{DEFAULT_PARTICLE_COUNT, GONIOMETER_POINTS, NUM_BANDS, SPECTROGRAM_BANDS, SPECTROGRAM_COLUMNS};
+pub use wave::{evaluate_wave, evaluate_wave_row, generate_mesh_indices, render_to_buffer, WaveMode, WaveParams};
+
+use wave::{Easing, ModeTransition};
+
+/// Largest chromatic aberration shift, in world units (about 2% of the screen)
+const MAX_ABERRATION: f32 = 0.04;
+/// Seconds of silence before the visualizer starts dimming
+const IDLE_DELAY_SECS: f32 = 2.0;
+/// Seconds the fade into the idle state takes
+const IDLE_FADE_SECS: f32 = 1.5;
+/// Width of the compressor's soft knee, centered on the threshold
+const COMPRESSION_KNEE: f32 = 0.1;
+/// Level the auto-gain maps the recent RMS peak to
+const AUTO_GAIN_TARGET: f32 = 0.9;
+/// Lowest tracked RMS peak, capping the auto-gain boost for near-silence
+const AUTO_GAIN_FLOOR: f32 = 0.1;
+/// Time constant for the RMS peak rising to a louder passage (seconds)
+const AUTO_GAIN_ATTACK_SECS: f32 = 0.05;
+/// Time constant for the RMS peak decaying during quieter passages (seconds)
+const AUTO_GAIN_RELEASE_SECS: f32 = 8.0;
+/// Largest buffer of raw frames a recording may hold (about 130 frames at 1080p)
+const MAX_RECORDING_BYTES: usize = 1 << 30;
 
 /// Initialize panic hook for better error messages in browser console
 pub fn set_panic_hook() {
@@ -18,11 +60,52 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
+static LOGGER_INIT: Once = Once::new();
+
+/// Initialize the console logger once per page
+///
+/// Safe to call from every `Visualizer` constructor; if the host already
+/// installed a logger, that one is kept.
+fn init_logger() {
+    LOGGER_INIT.call_once(|| {
+        let _ = console_log::init_with_level(log::Level::Info);
+    });
+}
+
 /// Audio frequency data passed from JavaScript
 #[wasm_bindgen]
 pub struct AudioData {
     frequency_data: Vec<f32>,
+    /// `frequency_data` normalized to 0.0 - 1.0 by the decibel range, refreshed on every update
+    normalized: Vec<f32>,
+    /// Time domain samples (the left channel when stereo data is supplied)
     time_domain_data: Vec<f32>,
+    /// Right channel time domain samples, empty until stereo data is supplied
+    right_time_domain_data: Vec<f32>,
+    /// Normalized magnitudes from the previous spectral flux call
+    previous_magnitudes: Vec<f32>,
+    /// Whether a length mismatch has already been logged
+    mismatch_warned: bool,
+    /// Decibel level mapped to 0.0 when normalizing
+    min_db: f32,
+    /// Decibel level mapped to 1.0 when normalizing
+    max_db: f32,
+    /// Window function the cached coefficients were computed for
+    window_kind: u32,
+    /// Precomputed window coefficients, matching the time domain length
+    window_coefficients: Vec<f32>,
+    /// Perceptual scaling applied to band levels (0 linear, 1 log, 2 sqrt)
+    band_scale: u32,
+    /// Weight of the previous frequency data when blending in new data (0.0 - 1.0)
+    smoothing: f32,
+    /// Sample rate of the analysed audio (Hz)
+    sample_rate: f32,
+    /// EQ gain points as supplied, spread evenly from 0 Hz to Nyquist (empty when flat)
+    eq_gains: Vec<f32>,
+    /// `eq_gains` resampled to one multiplier per frequency bin
+    eq_curve: Vec<f32>,
+    /// Band count `get_bands` analyses at, fixed by `set_band_count`
+    band_count: usize,
 }
 
 #[wasm_bindgen]
@@ -31,183 +114,2349 @@ impl AudioData {
     pub fn new(fft_size: usize) -> AudioData {
         AudioData {
             frequency_data: vec![0.0; fft_size / 2],
+            normalized: vec![0.0; fft_size / 2],
             time_domain_data: vec![0.0; fft_size],
+            right_time_domain_data: Vec::new(),
+            previous_magnitudes: Vec::new(),
+            mismatch_warned: false,
+            min_db: -100.0,
+            max_db: 0.0,
+            window_kind: 0,
+            window_coefficients: Vec::new(),
+            band_scale: 0,
+            smoothing: 0.0,
+            sample_rate: 44100.0,
+            eq_gains: Vec::new(),
+            eq_curve: Vec::new(),
+            band_count: NUM_BANDS,
+        }
+    }
+
+    /// Set the sample rate of the analysed audio, used for frequency/bin conversions
+    pub fn set_sample_rate(&mut self, hz: f32) -> Result<(), OscError> {
+        if !hz.is_finite() || hz <= 0.0 {
+            return Err(OscError::InvalidParam(format!("Invalid sample rate: {}", hz)));
+        }
+        self.sample_rate = hz;
+        Ok(())
+    }
+
+    /// Center frequency (Hz) of a frequency bin
+    pub fn bin_to_frequency(&self, bin: usize) -> f32 {
+        bin as f32 * self.sample_rate / self.time_domain_data.len().max(1) as f32
+    }
+
+    /// Frequency bin containing `hz`, clamped to the available bins
+    pub fn frequency_to_bin(&self, hz: f32) -> usize {
+        let bin = (hz * self.time_domain_data.len() as f32 / self.sample_rate).round().max(0.0) as usize;
+        bin.min(self.frequency_data.len().saturating_sub(1))
+    }
+
+    /// Set the frequency smoothing time constant, like `AnalyserNode.smoothingTimeConstant`
+    ///
+    /// Each update stores `old * smoothing + incoming * (1 - smoothing)`; 0.0 disables it.
+    pub fn set_smoothing_constant(&mut self, smoothing: f32) {
+        self.smoothing = smoothing.clamp(0.0, 0.99);
+    }
+
+    /// Set the scaling applied to band levels (0 linear, 1 log, 2 sqrt)
+    ///
+    /// Log and sqrt boost quiet signals while keeping 0.0 and 1.0 fixed.
+    pub fn set_band_scale(&mut self, scale: u32) {
+        self.band_scale = scale.min(2);
+    }
+
+    /// Set the decibel range mapped to 0.0 - 1.0 by all normalized getters
+    pub fn set_decibel_range(&mut self, min_db: f32, max_db: f32) -> Result<(), OscError> {
+        if !min_db.is_finite() || !max_db.is_finite() || min_db >= max_db {
+            return Err(OscError::InvalidParam(format!(
+                "Invalid decibel range: min {} must be below max {}",
+                min_db, max_db
+            )));
+        }
+        self.min_db = min_db;
+        self.max_db = max_db;
+        self.refresh_normalized();
+        Ok(())
+    }
+
+    /// Set a visual EQ: gain multipliers spread evenly from 0 Hz to Nyquist
+    ///
+    /// The curve is linearly interpolated to the bin count and applied to the normalized
+    /// magnitudes every getter reads. An empty slice restores the flat default (all 1.0).
+    pub fn set_eq(&mut self, gains: &[f32]) -> Result<(), OscError> {
+        if let Some(gain) = gains.iter().find(|gain| !gain.is_finite() || **gain < 0.0) {
+            return Err(OscError::InvalidParam(format!("Invalid EQ gain: {}", gain)));
+        }
+        self.eq_gains = if gains.iter().all(|&gain| gain == 1.0) { Vec::new() } else { gains.to_vec() };
+        self.refresh_eq_curve();
+        self.refresh_normalized();
+        Ok(())
+    }
+
+    /// Reallocate buffers for a new FFT size (power of two, 32 - 32768)
+    ///
+    /// Clears all audio data and the spectral flux history.
+    pub fn set_fft_size(&mut self, fft_size: usize) -> Result<(), OscError> {
+        if !fft_size.is_power_of_two() || !(32..=32768).contains(&fft_size) {
+            return Err(OscError::InvalidParam(format!(
+                "Invalid FFT size {}: must be a power of two between 32 and 32768",
+                fft_size
+            )));
         }
+
+        self.frequency_data = vec![0.0; fft_size / 2];
+        self.normalized = vec![0.0; fft_size / 2];
+        self.time_domain_data = vec![0.0; fft_size];
+        self.right_time_domain_data.clear();
+        self.previous_magnitudes.clear();
+        self.mismatch_warned = false;
+        self.refresh_eq_curve();
+        Ok(())
     }
 
     /// Update frequency data from JavaScript AnalyserNode
-    pub fn set_frequency_data(&mut self, data: &[f32]) {
-        let len = data.len().min(self.frequency_data.len());
-        self.frequency_data[..len].copy_from_slice(&data[..len]);
+    ///
+    /// Non-finite values (`-Infinity` for silent bins) are treated as the decibel floor.
+    /// Returns false (and warns once) if the length doesn't match the buffer.
+    pub fn set_frequency_data(&mut self, data: &[f32]) -> bool {
+        let (smoothing, floor) = (self.smoothing, self.min_db);
+        for (old, &incoming) in self.frequency_data.iter_mut().zip(data) {
+            *old = *old * smoothing + finite_or(incoming, floor) * (1.0 - smoothing);
+        }
+        self.refresh_normalized();
+        self.check_length("frequency", data.len(), self.frequency_data.len())
     }
 
     /// Update time domain data from JavaScript AnalyserNode
-    pub fn set_time_domain_data(&mut self, data: &[f32]) {
-        let len = data.len().min(self.time_domain_data.len());
-        self.time_domain_data[..len].copy_from_slice(&data[..len]);
+    ///
+    /// Non-finite samples are replaced with silence (0.0).
+    /// Returns false (and warns once) if the length doesn't match the buffer.
+    pub fn set_time_domain_data(&mut self, data: &[f32]) -> bool {
+        copy_finite(&data[..data.len().min(self.time_domain_data.len())], &mut self.time_domain_data);
+        self.check_length("time domain", data.len(), self.time_domain_data.len())
+    }
+
+    /// Analyse raw samples with the built-in FFT instead of an AnalyserNode
+    ///
+    /// Copies `samples` into the time domain data, applies the window last chosen with
+    /// `apply_window` (if any) to a scratch copy, and stores the magnitudes in dB as the
+    /// frequency data (smoothing applies as with `set_frequency_data`).
+    /// Returns false (and warns once) if the length doesn't match the FFT size, or the
+    /// FFT size isn't a power of two.
+    pub fn perform_fft(&mut self, samples: &[f32]) -> bool {
+        let len = self.time_domain_data.len();
+        if !self.check_length("FFT input", samples.len(), len) {
+            return false;
+        }
+        if !len.is_power_of_two() {
+            if !self.mismatch_warned {
+                self.mismatch_warned = true;
+                log::warn!("⚠️ FFT size {} is not a power of two", len);
+            }
+            return false;
+        }
+        copy_finite(samples, &mut self.time_domain_data);
+
+        let mut windowed = self.time_domain_data.clone();
+        if self.window_kind != 0 {
+            if self.window_coefficients.len() != len {
+                self.window_coefficients = window_coefficients(self.window_kind, len);
+            }
+            for (sample, &coefficient) in windowed.iter_mut().zip(&self.window_coefficients) {
+                *sample *= coefficient;
+            }
+        }
+
+        let magnitudes = fft::magnitudes_db(&windowed);
+        self.set_frequency_data(&magnitudes)
+    }
+
+    /// Update the right channel's time domain data, enabling the stereo meters
+    ///
+    /// `set_time_domain_data` then holds the left channel.
+    pub fn set_right_time_domain_data(&mut self, data: &[f32]) -> bool {
+        let len = self.time_domain_data.len();
+        self.right_time_domain_data.resize(len, 0.0);
+        copy_finite(&data[..data.len().min(len)], &mut self.right_time_domain_data);
+        self.check_length("right time domain", data.len(), len)
+    }
+
+    /// Get the left/right balance from per-channel RMS (-1.0 left ... 1.0 right)
+    ///
+    /// Returns 0.0 without stereo data or when either channel is silent.
+    pub fn get_balance(&self) -> f32 {
+        let Some((left, right, _)) = self.stereo_sums() else {
+            return 0.0;
+        };
+        let (left, right) = (left.sqrt(), right.sqrt());
+        (right - left) / (right + left)
+    }
+
+    /// Get the phase correlation of the two channels (-1.0 inverted ... 1.0 mono)
+    ///
+    /// Returns 0.0 without stereo data or when either channel is silent.
+    pub fn get_correlation(&self) -> f32 {
+        let Some((left, right, cross)) = self.stereo_sums() else {
+            return 0.0;
+        };
+        (cross / (left * right).sqrt()).clamp(-1.0, 1.0)
+    }
+
+    /// Apply a window function to the time domain data (0 none, 1 Hann, 2 Hamming, 3 Blackman)
+    ///
+    /// Call after `set_time_domain_data`; coefficients are cached per window and length.
+    pub fn apply_window(&mut self, window: u32) {
+        if !(1..=3).contains(&window) {
+            return;
+        }
+
+        let len = self.time_domain_data.len();
+        if self.window_kind != window || self.window_coefficients.len() != len {
+            self.window_kind = window;
+            self.window_coefficients = window_coefficients(window, len);
+        }
+
+        for (sample, &coefficient) in self.time_domain_data.iter_mut().zip(&self.window_coefficients) {
+            *sample *= coefficient;
+        }
+    }
+
+    /// Find the first crossing of `level` with the chosen slope in the time domain data
+    ///
+    /// Returns the sample offset of the crossing, or 0 if none is found.
+    pub fn find_trigger(&self, level: f32, rising: bool) -> usize {
+        self.time_domain_data
+            .windows(2)
+            .position(|pair| {
+                if rising {
+                    pair[0] < level && pair[1] >= level
+                } else {
+                    pair[0] > level && pair[1] <= level
+                }
+            })
+            .map_or(0, |i| i + 1)
     }
 
     /// Get normalized amplitude (0.0 - 1.0) from frequency data
     pub fn get_amplitude(&self) -> f32 {
-        if self.frequency_data.is_empty() {
+        if self.normalized.is_empty() {
             return 0.0;
         }
         
-        let sum: f32 = self.frequency_data.iter()
-            .map(|&x| {
-                // Convert from dB scale (-100 to 0) to linear (0 to 1)
-                let normalized = (x + 100.0) / 100.0;
-                normalized.max(0.0).min(1.0)
-            })
-            .sum();
+        let sum: f32 = self.normalized.iter().sum();
         
-        (sum / self.frequency_data.len() as f32).min(1.0)
+        (sum / self.normalized.len() as f32).min(1.0)
+    }
+
+    /// Estimate the loudest frequency (Hz), with parabolic interpolation between bins
+    ///
+    /// Returns 0.0 when the buffer is empty or silent (below the decibel range).
+    pub fn get_peak_frequency(&self) -> f32 {
+        let Some((peak, &peak_db)) = self
+            .frequency_data
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))
+        else {
+            return 0.0;
+        };
+        if self.normalized[peak] <= 0.0 {
+            return 0.0;
+        }
+
+        // Fit a parabola through the peak and its neighbours
+        let mut offset = 0.0;
+        if peak > 0 && peak + 1 < self.frequency_data.len() {
+            let left = self.frequency_data[peak - 1];
+            let right = self.frequency_data[peak + 1];
+            let denominator = left - 2.0 * peak_db + right;
+            if denominator.abs() > f32::EPSILON {
+                offset = (0.5 * (left - right) / denominator).clamp(-0.5, 0.5);
+            }
+        }
+
+        (peak as f32 + offset) * self.sample_rate / self.time_domain_data.len().max(1) as f32
+    }
+
+    /// Get the root mean square of the time domain data (0.0 - 1.0)
+    pub fn get_rms(&self) -> f32 {
+        if self.time_domain_data.is_empty() {
+            return 0.0;
+        }
+        let sum: f32 = self.time_domain_data.iter().map(|&x| x * x).sum();
+        (sum / self.time_domain_data.len() as f32).sqrt().min(1.0)
+    }
+
+    /// Get the min/max envelope of the time domain data over `num_points` columns
+    ///
+    /// Returns `2 * num_points` values as (min, max) pairs, one pair per column, so dense
+    /// buffers can be drawn without aliasing. Columns narrower than a sample repeat it;
+    /// an empty buffer gives zeros.
+    pub fn get_waveform_envelope(&self, num_points: usize) -> Vec<f32> {
+        let len = self.time_domain_data.len();
+        if len == 0 {
+            return vec![0.0; num_points * 2];
+        }
+
+        let mut envelope = Vec::with_capacity(num_points * 2);
+        for i in 0..num_points {
+            let start = (i * len / num_points).min(len - 1);
+            let end = ((i + 1) * len / num_points).max(start + 1);
+            let bucket = &self.time_domain_data[start..end];
+            envelope.push(bucket.iter().copied().fold(f32::INFINITY, f32::min));
+            envelope.push(bucket.iter().copied().fold(f32::NEG_INFINITY, f32::max));
+        }
+        envelope
+    }
+
+    /// Get up to `num_points` goniometer points as interleaved (side, mid) pairs
+    ///
+    /// Each (left, right) sample pair is rotated 45°: side `(r - l) / √2` is the x axis and
+    /// mid `(l + r) / √2` the y axis, so mono traces a vertical line and out-of-phase
+    /// audio a horizontal one. Without right channel data the left channel is used for both.
+    pub fn get_goniometer_points(&self, num_points: usize) -> Vec<f32> {
+        let len = self.time_domain_data.len();
+        if len == 0 || num_points == 0 {
+            return Vec::new();
+        }
+        let right = if self.right_time_domain_data.len() == len {
+            &self.right_time_domain_data
+        } else {
+            &self.time_domain_data
+        };
+
+        let count = num_points.min(len);
+        let mut points = Vec::with_capacity(count * 2);
+        for i in 0..count {
+            let index = i * len / count;
+            let (l, r) = (self.time_domain_data[index], right[index]);
+            points.push((r - l) * std::f32::consts::FRAC_1_SQRT_2);
+            points.push((l + r) * std::f32::consts::FRAC_1_SQRT_2);
+        }
+        points
+    }
+
+    /// Get the spectral centroid (Hz): the magnitude-weighted mean frequency
+    pub fn get_spectral_centroid(&self) -> f32 {
+        let mut weighted = 0.0;
+        let mut total = 0.0;
+        for (bin, &magnitude) in self.normalized.iter().enumerate() {
+            weighted += magnitude * self.bin_to_frequency(bin);
+            total += magnitude;
+        }
+        if total > 0.0 {
+            weighted / total
+        } else {
+            0.0
+        }
+    }
+
+    /// Fix the band resolution `get_bands` analyses at (default 8, matching the shader)
+    ///
+    /// Smoothing and EQ are applied per bin, so they carry over unchanged; fixing the
+    /// count keeps every consumer of `get_bands` on the same resolution.
+    pub fn set_band_count(&mut self, num_bands: usize) -> Result<(), OscError> {
+        if num_bands == 0 {
+            return Err(OscError::InvalidParam("Band count must be at least 1".to_string()));
+        }
+        self.band_count = num_bands;
+        Ok(())
+    }
+
+    /// Band count set by `set_band_count`
+    pub fn band_count(&self) -> usize {
+        self.band_count
+    }
+
+    /// Get frequency bands at the fixed band count
+    pub fn get_bands(&self) -> Vec<f32> {
+        self.get_frequency_bands(self.band_count)
     }
 
     /// Get frequency bands for visualization
     pub fn get_frequency_bands(&self, num_bands: usize) -> Vec<f32> {
+        let mut bands = vec![0.0; num_bands];
+        self.write_frequency_bands(&mut bands);
+        bands
+    }
+
+    /// Fill `out` with frequency bands, using its length as the band count
+    ///
+    /// Allocation-free alternative to `get_frequency_bands` for the render loop.
+    pub fn write_frequency_bands(&self, out: &mut [f32]) {
+        let num_bands = out.len();
         if self.frequency_data.is_empty() || num_bands == 0 {
-            return vec![0.0; num_bands];
+            out.fill(0.0);
+            return;
+        }
+
+        if num_bands > self.frequency_data.len() {
+            self.interpolate_bands(out);
+            return;
         }
 
         let samples_per_band = self.frequency_data.len() / num_bands;
-        let mut bands = Vec::with_capacity(num_bands);
 
-        for i in 0..num_bands {
+        for (i, band) in out.iter_mut().enumerate() {
             let start = i * samples_per_band;
             let end = ((i + 1) * samples_per_band).min(self.frequency_data.len());
             
-            let avg: f32 = self.frequency_data[start..end].iter()
-                .map(|&x| ((x + 100.0) / 100.0).max(0.0).min(1.0))
-                .sum::<f32>() / (end - start) as f32;
+            let avg: f32 = self.normalized[start..end].iter().sum::<f32>() / (end - start) as f32;
             
-            bands.push(avg);
+            *band = self.scale_band(avg);
         }
+    }
 
-        bands
+    /// Get `num_bands` bands spread across only the bins between `start_hz` and `end_hz`
+    ///
+    /// The range is clamped to 0 - Nyquist; returns zeros if `start_hz >= end_hz`.
+    pub fn get_frequency_bands_range(&self, num_bands: usize, start_hz: f32, end_hz: f32) -> Vec<f32> {
+        let nyquist = self.sample_rate * 0.5;
+        let start_hz = start_hz.clamp(0.0, nyquist);
+        let end_hz = end_hz.clamp(0.0, nyquist);
+        if self.frequency_data.is_empty() || num_bands == 0 || start_hz >= end_hz {
+            return vec![0.0; num_bands];
+        }
+
+        let first = self.frequency_to_bin(start_hz);
+        let bins = self.frequency_to_bin(end_hz) + 1 - first;
+        (0..num_bands)
+            .map(|i| {
+                // Every band covers at least one bin, repeating bins when zoomed in past bin resolution
+                let start = first + i * bins / num_bands;
+                let end = (first + (i + 1) * bins / num_bands).max(start + 1);
+                let avg = self.normalized[start..end].iter().sum::<f32>() / (end - start) as f32;
+                self.scale_band(avg)
+            })
+            .collect()
+    }
+
+    /// Get a copy of the full spectrum, normalized to 0.0 - 1.0 by the decibel range
+    pub fn get_normalized_spectrum(&self) -> Vec<f32> {
+        self.normalized.clone()
+    }
+
+    /// Get a copy of the spectrum in decibels (typically -100 to 0), one value per bin
+    ///
+    /// The values are returned as stored, after smoothing but without the decibel range,
+    /// EQ or band scale the normalized getters apply.
+    pub fn get_raw_spectrum(&self) -> Vec<f32> {
+        self.frequency_data.clone()
+    }
+
+    /// Get the normalized spectrum resampled to `num_points` by linear interpolation
+    ///
+    /// Unlike `get_frequency_bands`, which averages bins down, this smoothly upsamples
+    /// for drawing at more points than bins. Returns zeros for an empty buffer.
+    pub fn get_interpolated_spectrum(&self, num_points: usize) -> Vec<f32> {
+        let mut points = vec![0.0; num_points];
+        if !self.normalized.is_empty() {
+            self.interpolate_normalized(&mut points);
+        }
+        points
+    }
+
+    /// Get spectral flux (0.0 - 1.0): how much the spectrum grew since the last call
+    ///
+    /// Sums the positive magnitude differences against the previous frame and
+    /// normalizes by bin count. Returns 0.0 on the first call (no history).
+    pub fn get_spectral_flux(&mut self) -> f32 {
+        let has_history = self.previous_magnitudes.len() == self.frequency_data.len();
+        if !has_history {
+            self.previous_magnitudes = vec![0.0; self.frequency_data.len()];
+        }
+
+        let mut flux = 0.0;
+        for (prev, &magnitude) in self.previous_magnitudes.iter_mut().zip(&self.normalized) {
+            flux += (magnitude - *prev).max(0.0);
+            *prev = magnitude;
+        }
+
+        if !has_history || self.frequency_data.is_empty() {
+            return 0.0;
+        }
+
+        (flux / self.frequency_data.len() as f32).min(1.0)
+    }
+}
+
+impl AudioData {
+    /// Recompute `normalized` from the dB frequency data (min_db to max_db mapped to 0 to 1)
+    ///
+    /// Runs once per update so the getters share one conversion; the loop is a plain
+    /// multiply-add and clamp over slices so it autovectorizes.
+    fn refresh_normalized(&mut self) {
+        let scale = 1.0 / (self.max_db - self.min_db);
+        let offset = -self.min_db * scale;
+        self.normalized.resize(self.frequency_data.len(), 0.0);
+        for (out, &x) in self.normalized.iter_mut().zip(&self.frequency_data) {
+            *out = (x * scale + offset).clamp(0.0, 1.0);
+        }
+        if !self.eq_curve.is_empty() {
+            for (out, &gain) in self.normalized.iter_mut().zip(&self.eq_curve) {
+                *out = (*out * gain).min(1.0);
+            }
+        }
+        debug_assert!(self.normalized.iter().all(|level| level.is_finite()));
+    }
+
+    /// Resample the EQ gain points to the current bin count
+    fn refresh_eq_curve(&mut self) {
+        self.eq_curve.clear();
+        if !self.eq_gains.is_empty() {
+            self.eq_curve.resize(self.frequency_data.len(), 1.0);
+            resample_linear(&self.eq_gains, &mut self.eq_curve);
+        }
+    }
+
+    /// Upsample the spectrum to more bands than bins by linear interpolation
+    fn interpolate_bands(&self, out: &mut [f32]) {
+        self.interpolate_normalized(out);
+        for band in out.iter_mut() {
+            *band = self.scale_band(*band);
+        }
+    }
+
+    /// Fill `out` with the normalized bins linearly resampled across its length
+    ///
+    /// The first and last points land exactly on the first and last bins.
+    /// `normalized` must not be empty.
+    fn interpolate_normalized(&self, out: &mut [f32]) {
+        resample_linear(&self.normalized, out);
+    }
+
+    /// Sums of left², right² and left·right, or None without stereo data or with a silent channel
+    fn stereo_sums(&self) -> Option<(f32, f32, f32)> {
+        let (mut left, mut right, mut cross) = (0.0, 0.0, 0.0);
+        for (&l, &r) in self.time_domain_data.iter().zip(&self.right_time_domain_data) {
+            left += l * l;
+            right += r * r;
+            cross += l * r;
+        }
+        (left > f32::EPSILON && right > f32::EPSILON).then_some((left, right, cross))
+    }
+
+    /// Spectral centroid relative to the Nyquist frequency (0.0 - 1.0)
+    fn get_normalized_centroid(&self) -> f32 {
+        (self.get_spectral_centroid() / (self.sample_rate * 0.5)).clamp(0.0, 1.0)
+    }
+
+    /// Apply the band scale to a normalized level
+    fn scale_band(&self, level: f32) -> f32 {
+        match self.band_scale {
+            1 => (1.0 + 9.0 * level).log10(),
+            2 => level.sqrt(),
+            _ => level,
+        }
+    }
+
+    fn check_length(&mut self, kind: &str, received: usize, expected: usize) -> bool {
+        if received == expected {
+            return true;
+        }
+        if !self.mismatch_warned {
+            self.mismatch_warned = true;
+            log::warn!(
+                "⚠️ {} data length {} doesn't match expected {} (check AnalyserNode fftSize)",
+                kind, received, expected
+            );
+        }
+        false
+    }
+}
+
+/// Every derived audio metric for one frame, fetched in a single call
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AudioFeatures {
+    /// Broadband amplitude (0.0 - 1.0)
+    pub amplitude: f32,
+    /// Time domain RMS (0.0 - 1.0)
+    pub rms: f32,
+    /// Average of the lowest quarter of the bands (0.0 - 1.0)
+    pub bass: f32,
+    /// Average of the bands in between (0.0 - 1.0)
+    pub mid: f32,
+    /// Average of the highest quarter of the bands (0.0 - 1.0)
+    pub treble: f32,
+    /// Spectral centroid (Hz)
+    pub centroid: f32,
+    /// Loudest frequency (Hz)
+    pub peak_frequency: f32,
+    /// Whether the last rendered frame was a beat
+    pub beat: bool,
+}
+
+/// Coefficients of a window function (1 Hann, 2 Hamming, 3 Blackman) over `len` samples
+fn window_coefficients(window: u32, len: usize) -> Vec<f32> {
+    let denominator = len.saturating_sub(1).max(1) as f32;
+    (0..len)
+        .map(|n| {
+            let phase = std::f32::consts::TAU * n as f32 / denominator;
+            match window {
+                1 => 0.5 - 0.5 * phase.cos(),
+                2 => 0.54 - 0.46 * phase.cos(),
+                3 => 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos(),
+                _ => 1.0,
+            }
+        })
+        .collect()
+}
+
+/// `value`, or `fallback` if it is NaN or infinite
+fn finite_or(value: f32, fallback: f32) -> f32 {
+    if value.is_finite() {
+        value
+    } else {
+        fallback
+    }
+}
+
+/// Copy `source` into the start of `out`, replacing non-finite samples with 0.0
+fn copy_finite(source: &[f32], out: &mut [f32]) {
+    for (sample, &incoming) in out.iter_mut().zip(source) {
+        *sample = finite_or(incoming, 0.0);
+    }
+    debug_assert!(out.iter().all(|sample| sample.is_finite()));
+}
+
+/// Linearly resample `source` across the length of `out`, end points aligned
+///
+/// `source` must not be empty.
+fn resample_linear(source: &[f32], out: &mut [f32]) {
+    let num_points = out.len();
+    let last = source.len() - 1;
+    let step = if num_points > 1 { last as f32 / (num_points - 1) as f32 } else { 0.0 };
+
+    for (i, point) in out.iter_mut().enumerate() {
+        let position = i as f32 * step;
+        let lower = (position as usize).min(last);
+        let upper = (lower + 1).min(last);
+        let fraction = position - lower as f32;
+        *point = source[lower] + (source[upper] - source[lower]) * fraction;
+    }
+}
+
+/// Synthesize a plausible amplitude and band levels from time alone
+///
+/// Slow swells plus a 120 BPM beat that hits the low bands hardest.
+fn demo_signal(time: f32) -> (f32, Vec<f32>) {
+    let swell = 0.5 + 0.3 * (time * 0.23).sin() + 0.2 * (time * 0.61 + 1.3).sin();
+    let beat = (-(time * 2.0).fract() * 6.0).exp();
+
+    let bands: Vec<f32> = (0..NUM_BANDS)
+        .map(|i| {
+            let position = i as f32 / (NUM_BANDS - 1) as f32;
+            let shimmer = 0.5 + 0.5 * (time * (1.7 + position * 3.1) + i as f32).sin();
+            let level = swell * (0.6 - position * 0.3) + beat * (1.0 - position) * 0.5 + shimmer * 0.15;
+            level.clamp(0.0, 1.0)
+        })
+        .collect();
+
+    let amplitude = bands.iter().sum::<f32>() / NUM_BANDS as f32;
+    (amplitude, bands)
+}
+
+/// Soft-knee compression of a 0.0 - 1.0 level: linear below `threshold`, divided by `ratio` above
+///
+/// Blends quadratically across `COMPRESSION_KNEE` so the curve has no corner.
+fn soft_knee(level: f32, threshold: f32, ratio: f32) -> f32 {
+    let half_knee = COMPRESSION_KNEE * 0.5;
+    let over = level - threshold;
+    if over <= -half_knee {
+        level
+    } else if over >= half_knee {
+        threshold + over / ratio
+    } else {
+        let into_knee = over + half_knee;
+        level + (1.0 / ratio - 1.0) * into_knee * into_knee / (2.0 * COMPRESSION_KNEE)
+    }
+}
+
+/// `performance.now()` of the global scope, which works in both windows and workers
+fn performance_now() -> Result<f64, OscError> {
+    js_sys::Reflect::get(&js_sys::global(), &"performance".into())
+        .ok()
+        .and_then(|performance| performance.dyn_into::<web_sys::Performance>().ok())
+        .map(|performance| performance.now())
+        .ok_or_else(|| OscError::Environment("No performance object".to_string()))
+}
+
+/// Look up a canvas element by id
+fn find_canvas(canvas_id: &str) -> Result<HtmlCanvasElement, OscError> {
+    let window = web_sys::window()
+        .ok_or_else(|| OscError::Environment("No window object".to_string()))?;
+    let document = window.document()
+        .ok_or_else(|| OscError::Environment("No document object".to_string()))?;
+    document.get_element_by_id(canvas_id)
+        .ok_or_else(|| OscError::CanvasNotFound(format!("Canvas '{}' not found", canvas_id)))?
+        .dyn_into::<HtmlCanvasElement>()
+        .map_err(|_| OscError::CanvasNotFound(format!("Element '{}' is not a canvas", canvas_id)))
+}
+
+/// Active rendering backend
+///
+/// Only the canvas `init` methods create one, and those are wasm32-only.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+enum RenderBackend {
+    Wgpu(Box<Renderer>),
+    Canvas2d(CanvasFallback),
+}
+
+impl RenderBackend {
+    fn render(&mut self, time: f32, params: &WaveParams, bands: &[f32]) -> Result<(), OscError> {
+        match self {
+            RenderBackend::Wgpu(renderer) => renderer.render(time, params, bands),
+            RenderBackend::Canvas2d(fallback) => fallback.render(time, params),
+        }
+    }
+
+    fn supports_mode(&self, mode: WaveMode) -> bool {
+        match self {
+            RenderBackend::Wgpu(renderer) => renderer.supports_mode(mode),
+            // The CPU fallback can evaluate every height-field mode
+            RenderBackend::Canvas2d(_) => {
+                !matches!(mode, WaveMode::Spectrogram | WaveMode::Bars3D | WaveMode::Goniometer)
+            }
+        }
+    }
+
+    fn resize(&mut self, width: u32, height: u32) -> Result<(), OscError> {
+        match self {
+            RenderBackend::Wgpu(renderer) => renderer.resize(width, height),
+            RenderBackend::Canvas2d(fallback) => fallback.resize(width, height),
+        }
     }
 }
 
+/// Audio values for one frame, read under a single lock of the shared `AudioData`
+struct AudioSnapshot {
+    amplitude: f32,
+    bands: Vec<f32>,
+    rms: f32,
+    centroid: f32,
+    flux: f32,
+    /// Spectrogram column, when one is due this frame
+    spectrum: Option<Vec<f32>>,
+    /// Goniometer points, when the goniometer is visible
+    goniometer: Option<Vec<f32>>,
+}
+
+/// Renderer settings made through the Visualizer, reapplied to every new renderer
+#[derive(Debug, Clone, Default)]
+struct RendererSettings {
+    render_scale: Option<f32>,
+    bar_grid: Option<(u32, u32)>,
+    background: Option<[f32; 3]>,
+    blend: Option<u32>,
+    wireframe: bool,
+}
+
+/// Offline recording: frames rendered at a fixed time step
+struct Recording {
+    fps: u32,
+    /// Render time (seconds) of the first recorded frame
+    start_time: f32,
+    frame_count: u32,
+    /// Raw RGBA frames, back to back
+    frames: Vec<u8>,
+}
+
 /// Main Visualizer struct - entry point for the application
 #[wasm_bindgen]
 pub struct Visualizer {
-    renderer: Option<Renderer>,
+    renderer: Option<RenderBackend>,
     audio_data: Arc<Mutex<AudioData>>,
     wave_params: WaveParams,
     start_time: f64,
+    /// Render time (seconds) of the most recent frame
+    time: f32,
+    transition: Option<ModeTransition>,
+    /// Curve applied to transition progress before it becomes the blend mix
+    transition_easing: Easing,
+    particle_count: u32,
+    /// Kept across `destroy` and `init`, like `particle_count`
+    renderer_settings: RendererSettings,
+    smoothed_amplitude: f32,
+    recording: Option<Recording>,
+    shake: CameraShake,
+    /// Frequency/speed jumps kicked by spectral flux transients
+    jitter: TransientJitter,
+    /// Oscilloscope trigger level and slope (rising when true)
+    trigger: Option<(f32, bool)>,
+    /// Time (seconds) set by `set_time`, overriding the timestamp
+    manual_time: Option<f32>,
+    /// Always render at `manual_time` instead of the timestamp
+    use_fixed_time: bool,
+    /// Automatic hue rotation speed (degrees per second)
+    hue_rotation: f32,
+    /// Hue offset accumulated by the rotation, added to the base hue
+    hue_offset: f32,
+    /// Amplitude below which audio counts as silence (0.0 disables idle dimming)
+    idle_threshold: f32,
+    /// Brightness and amplitude scale once fully idle
+    idle_dim: f32,
+    /// Seconds the audio has stayed below the idle threshold
+    quiet_time: f32,
+    /// Progress into the idle state (0.0 active, 1.0 fully dimmed)
+    idle_level: f32,
+    /// Amplitude multiplier with no audio
+    reactivity_base: f32,
+    /// Amplitude multiplier added per unit of (curved) audio amplitude
+    reactivity_scale: f32,
+    /// Exponent applied to the audio amplitude before scaling
+    reactivity_gamma: f32,
+    /// Feature driving the reactive amplitude (`None` uses the broadband amplitude)
+    reactivity_source: Option<ReactiveSource>,
+    /// Level above which the reactive amplitude is compressed
+    compression_threshold: f32,
+    /// Compression ratio above the threshold (1.0 disables compression)
+    compression_ratio: f32,
+    /// Spectrogram scroll rate in columns per second
+    spectrogram_speed: f32,
+    /// Fraction of a spectrogram column accumulated since the last write
+    spectrogram_progress: f32,
+    /// Clamp `set_amplitude`, `set_frequency` and `set_speed` to their safe ranges
+    clamp_enabled: bool,
+    /// Drive reactivity from a synthesized signal instead of the audio data
+    demo_mode: bool,
+    /// Audio feature to parameter bindings, applied additively each frame
+    reactive_bindings: Vec<ReactiveBinding>,
+    /// Onset detection on the bass energy, updated every frame
+    beat_detector: BeatDetector,
+    /// Hold the current audio frame, ignoring `update_audio`
+    audio_frozen: bool,
+    /// Drive the `pulse` uniform from the estimated tempo
+    tempo_pulse: bool,
+    /// Normalize the reactive amplitude against the recent RMS peak
+    auto_gain: bool,
+    /// Running RMS peak tracked by the auto-gain
+    rms_peak: f32,
+    /// Warm-up measuring the audio to tune auto-gain and beat sensitivity
+    calibration: Option<Calibration>,
+    /// JavaScript function called with the amplitude whenever a rendered frame is a beat
+    beat_callback: Option<js_sys::Function>,
+    /// Set while the beat callback runs, so a nested render doesn't call it again
+    in_beat_callback: bool,
 }
 
 #[wasm_bindgen]
 impl Visualizer {
     /// Create a new Visualizer attached to an HTML canvas element
     #[wasm_bindgen(constructor)]
-    pub fn new(canvas_id: &str) -> Result<Visualizer, JsValue> {
+    pub fn new(canvas_id: &str) -> Result<Visualizer, OscError> {
         set_panic_hook();
-        console_log::init_with_level(log::Level::Info)
-            .map_err(|e| JsValue::from_str(&format!("Failed to init logger: {}", e)))?;
+        init_logger();
         
         log::info!("🎵 Cyber-Oscilloscope initializing...");
 
-        let window = web_sys::window()
-            .ok_or_else(|| JsValue::from_str("No window object"))?;
-        let document = window.document()
-            .ok_or_else(|| JsValue::from_str("No document object"))?;
-        let canvas = document.get_element_by_id(canvas_id)
-            .ok_or_else(|| JsValue::from_str(&format!("Canvas '{}' not found", canvas_id)))?
-            .dyn_into::<HtmlCanvasElement>()
-            .map_err(|_| JsValue::from_str("Element is not a canvas"))?;
+        find_canvas(canvas_id)?;
+        Ok(Visualizer::with_start_time(performance_now()?))
+    }
 
-        let performance = window.performance()
-            .ok_or_else(|| JsValue::from_str("No performance object"))?;
-        let start_time = performance.now();
+    /// Create a Visualizer without `window` or `document`, e.g. inside a Web Worker
+    ///
+    /// Call `init_offscreen` with the transferred `OffscreenCanvas`, then feed audio
+    /// posted from the main thread through `update_audio`.
+    pub fn new_offscreen() -> Result<Visualizer, OscError> {
+        set_panic_hook();
+        init_logger();
 
-        Ok(Visualizer {
-            renderer: None,
-            audio_data: Arc::new(Mutex::new(AudioData::new(2048))),
-            wave_params: WaveParams::default(),
-            start_time,
-        })
+        log::info!("🎵 Cyber-Oscilloscope initializing (offscreen)...");
+
+        Ok(Visualizer::with_start_time(performance_now()?))
+    }
+
+    /// Create a Visualizer that reacts to the same audio data as `other`
+    ///
+    /// Both instances share one `AudioData` behind the mutex: feed audio through
+    /// either one (once per frame), and `set_fft_size` affects both. Each render
+    /// locks it once, to snapshot everything the frame reads.
+    pub fn with_shared_audio(canvas_id: &str, other: &Visualizer) -> Result<Visualizer, OscError> {
+        let mut visualizer = Visualizer::new(canvas_id)?;
+        visualizer.audio_data = Arc::clone(&other.audio_data);
+        Ok(visualizer)
     }
 
     /// Async initialization of WGPU renderer
-    pub async fn init(&mut self, canvas_id: &str) -> Result<(), JsValue> {
+    #[cfg(target_arch = "wasm32")]
+    pub async fn init(&mut self, canvas_id: &str) -> Result<(), OscError> {
+        self.init_with_config(canvas_id, RendererConfig::default()).await
+    }
+
+    /// Async initialization of WGPU renderer with explicit creation options
+    #[cfg(target_arch = "wasm32")]
+    pub async fn init_with_config(&mut self, canvas_id: &str, config: RendererConfig) -> Result<(), OscError> {
         log::info!("⚡ Initializing WGPU renderer...");
         
-        let window = web_sys::window()
-            .ok_or_else(|| JsValue::from_str("No window object"))?;
-        let document = window.document()
-            .ok_or_else(|| JsValue::from_str("No document object"))?;
-        let canvas = document.get_element_by_id(canvas_id)
-            .ok_or_else(|| JsValue::from_str(&format!("Canvas '{}' not found", canvas_id)))?
-            .dyn_into::<HtmlCanvasElement>()
-            .map_err(|_| JsValue::from_str("Element is not a canvas"))?;
-
-        let renderer = Renderer::new(canvas).await?;
-        self.renderer = Some(renderer);
-        
-        log::info!("✨ Renderer initialized successfully!");
+        let canvas = find_canvas(canvas_id)?;
+
+        match Renderer::new(canvas.clone(), config).await {
+            Ok(mut renderer) => {
+                self.configure_renderer(&mut renderer);
+                self.renderer = Some(RenderBackend::Wgpu(Box::new(renderer)));
+                log::info!("✨ Renderer initialized successfully!");
+            }
+            Err(e) => {
+                log::warn!("⚠️ WGPU unavailable ({:?}), falling back to Canvas 2D", e);
+                self.renderer = Some(RenderBackend::Canvas2d(CanvasFallback::new(canvas)?));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Async initialization of the WGPU renderer on an `OffscreenCanvas`
+    ///
+    /// There is no Canvas 2D fallback offscreen, so WGPU errors are returned.
+    #[cfg(target_arch = "wasm32")]
+    pub async fn init_offscreen(&mut self, canvas: OffscreenCanvas) -> Result<(), OscError> {
+        log::info!("⚡ Initializing offscreen WGPU renderer...");
+
+        let mut renderer = Renderer::new_offscreen(canvas, RendererConfig::default()).await?;
+        self.configure_renderer(&mut renderer);
+        self.renderer = Some(RenderBackend::Wgpu(Box::new(renderer)));
+        log::info!("✨ Offscreen renderer initialized successfully!");
         Ok(())
     }
 
     /// Update audio data from JavaScript
-    pub fn update_audio(&mut self, frequency_data: &[f32], time_domain_data: &[f32]) {
+    ///
+    /// Returns false if either buffer length doesn't match the FFT size.
+    /// Ignored (returning true) while the audio is frozen.
+    pub fn update_audio(&mut self, frequency_data: &[f32], time_domain_data: &[f32]) -> bool {
+        if self.audio_frozen {
+            return true;
+        }
+        if let Ok(mut audio) = self.audio_data.lock() {
+            let frequency_ok = audio.set_frequency_data(frequency_data);
+            let time_domain_ok = audio.set_time_domain_data(time_domain_data);
+            frequency_ok && time_domain_ok
+        } else {
+            false
+        }
+    }
+
+    /// Update audio from raw samples using the built-in FFT (see `AudioData::perform_fft`)
+    ///
+    /// Ignored (returning true) while the audio is frozen.
+    pub fn update_audio_samples(&mut self, samples: &[f32]) -> bool {
+        if self.audio_frozen {
+            return true;
+        }
+        self.audio_data.lock().map(|mut audio| audio.perform_fft(samples)).unwrap_or(false)
+    }
+
+    /// Hold the current audio frame for inspection; the animation keeps running
+    ///
+    /// While frozen `update_audio` is a no-op, so the getters keep reading the held frame.
+    pub fn freeze_audio(&mut self, frozen: bool) {
+        self.audio_frozen = frozen;
+        log::info!("🧊 Audio {}", if frozen { "frozen" } else { "unfrozen" });
+    }
+
+    /// Call `callback(amplitude)` from `render` whenever the beat detector fires
+    pub fn set_beat_callback(&mut self, callback: js_sys::Function) {
+        self.beat_callback = Some(callback);
+    }
+
+    /// Stop calling the beat callback
+    pub fn clear_beat_callback(&mut self) {
+        self.beat_callback = None;
+    }
+
+    /// Match the AnalyserNode `fftSize` (power of two, 32 - 32768)
+    pub fn set_fft_size(&mut self, fft_size: usize) -> Result<(), OscError> {
+        if let Ok(mut audio) = self.audio_data.lock() {
+            audio.set_fft_size(fft_size)?;
+            log::info!("🎚️ FFT size set to {}", fft_size);
+        }
+        Ok(())
+    }
+
+    /// Match the AudioContext `sampleRate` (Hz)
+    pub fn set_sample_rate(&mut self, hz: f32) -> Result<(), OscError> {
         if let Ok(mut audio) = self.audio_data.lock() {
-            audio.set_frequency_data(frequency_data);
-            audio.set_time_domain_data(time_domain_data);
+            audio.set_sample_rate(hz)?;
+            log::info!("🎚️ Sample rate set to {} Hz", hz);
         }
+        Ok(())
     }
 
     /// Set wave visualization mode
-    pub fn set_mode(&mut self, mode: u32) {
-        self.wave_params.mode = WaveMode::from_u32(mode);
+    pub fn set_mode(&mut self, mode: u32) -> Result<(), OscError> {
+        let mode = self.supported_mode(mode)?;
+        self.transition = None;
+        self.wave_params.mode = mode;
+        self.clear_framebuffer();
         log::info!("🌊 Wave mode changed to: {:?}", self.wave_params.mode);
+        Ok(())
     }
 
-    /// Set wave amplitude
-    pub fn set_amplitude(&mut self, amplitude: f32) {
-        self.wave_params.amplitude = amplitude.max(0.0).min(2.0);
+    /// Fully clear the persistence trails on the next frame, regardless of the decay
+    ///
+    /// Called on mode changes so the previous mode's trails don't ghost into the new one.
+    pub fn clear_framebuffer(&mut self) {
+        if let Some(RenderBackend::Wgpu(ref mut renderer)) = self.renderer {
+            renderer.clear_history();
+        }
     }
 
-    /// Set wave frequency
-    pub fn set_frequency(&mut self, frequency: f32) {
-        self.wave_params.frequency = frequency.max(0.1).min(20.0);
+    /// Modes the active renderer can display (all modes before `init`)
+    pub fn supported_modes(&self) -> Vec<u32> {
+        WaveMode::ALL
+            .iter()
+            .filter(|&&mode| self.renderer.as_ref().is_none_or(|r| r.supports_mode(mode)))
+            .map(|&mode| mode as u32)
+            .collect()
     }
 
-    /// Set wave speed
-    pub fn set_speed(&mut self, speed: f32) {
-        self.wave_params.speed = speed.max(0.1).min(5.0);
+    /// Set wave visualization mode and apply its tuned amplitude, frequency and speed
+    ///
+    /// Unlike `set_mode`, which carries the current params across modes.
+    pub fn set_mode_with_defaults(&mut self, mode: u32) -> Result<(), OscError> {
+        self.set_mode(mode)?;
+        let defaults = self.wave_params.mode.default_params();
+        self.wave_params.amplitude = defaults.amplitude;
+        self.wave_params.frequency = defaults.frequency;
+        self.wave_params.speed = defaults.speed;
+        Ok(())
     }
 
-    /// Set color hue (0-360)
-    pub fn set_hue(&mut self, hue: f32) {
-        self.wave_params.hue = hue % 360.0;
+    /// Cross-fade to a new wave mode over `duration_secs`
+    pub fn transition_to(&mut self, mode: u32, duration_secs: f32) -> Result<(), OscError> {
+        let mode = self.supported_mode(mode)?;
+        let duration = duration_secs.max(0.0);
+
+        let transition = match self.transition {
+            Some(current) => current.retarget(mode, self.time, duration),
+            None if mode == self.wave_params.mode => return Ok(()),
+            None => ModeTransition {
+                from_mode: self.wave_params.mode,
+                to_mode: mode,
+                start_time: self.time,
+                duration,
+            },
+        };
+
+        self.wave_params.mode = transition.from_mode;
+        self.transition = Some(transition);
+        self.clear_framebuffer();
+        log::info!("🌊 Wave mode transitioning to: {:?}", mode);
+        Ok(())
     }
 
-    /// Render a single frame
-    pub fn render(&mut self, timestamp: f64) -> Result<(), JsValue> {
-        if let Some(ref mut renderer) = self.renderer {
-            let time = (timestamp - self.start_time) / 1000.0;
-            
-            let amplitude = if let Ok(audio) = self.audio_data.lock() {
-                audio.get_amplitude()
-            } else {
-                0.0
-            };
+    /// Choose the transition curve: 0 linear, 1 ease-in-out, 2 ease-out, 3 smoothstep
+    pub fn set_transition_easing(&mut self, easing: u32) {
+        self.transition_easing = Easing::from_u32(easing);
+    }
 
-            // Apply audio reactivity to wave params
-            let mut params = self.wave_params.clone();
-            params.amplitude *= 0.5 + amplitude * 1.5;
-            
-            renderer.render(time as f32, &params)?;
+    /// Set wave amplitude (0.0 - 2.0 unless clamping is disabled)
+    pub fn set_amplitude(&mut self, amplitude: f32) {
+        if let Some(amplitude) = self.limit(amplitude, 0.0, 2.0) {
+            self.wave_params.amplitude = amplitude;
         }
-        Ok(())
     }
 
-    /// Resize the canvas
-    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), JsValue> {
-        if let Some(ref mut renderer) = self.renderer {
-            renderer.resize(width, height)?;
+    /// Set wave frequency (0.1 - 20.0 unless clamping is disabled)
+    pub fn set_frequency(&mut self, frequency: f32) {
+        if let Some(frequency) = self.limit(frequency, 0.1, 20.0) {
+            self.wave_params.frequency = frequency;
         }
+    }
+
+    /// Set wave speed (0.1 - 5.0 unless clamping is disabled)
+    pub fn set_speed(&mut self, speed: f32) {
+        if let Some(speed) = self.limit(speed, 0.1, 5.0) {
+            self.wave_params.speed = speed;
+        }
+    }
+
+    /// Clamp amplitude, frequency and speed to their safe ranges (on by default)
+    ///
+    /// When disabled those setters accept any finite value for experimental looks.
+    pub fn set_clamp_enabled(&mut self, enabled: bool) {
+        self.clamp_enabled = enabled;
+    }
+
+    /// Shift the wave phase (radians), e.g. to offset visualizers sharing one audio source
+    pub fn set_phase(&mut self, radians: f32) {
+        if radians.is_finite() {
+            self.wave_params.phase = radians;
+        }
+    }
+
+    /// Set color hue (0-360)
+    pub fn set_hue(&mut self, hue: f32) {
+        self.wave_params.hue = palette::wrap_hue(hue);
+    }
+
+    /// Set the secondary glow hue, relative to the primary hue (0-360)
+    pub fn set_hue2(&mut self, hue2: f32) {
+        self.wave_params.hue2 = palette::wrap_hue(hue2);
+    }
+
+    /// Rotate the hue automatically by `degrees_per_sec` (0.0 disables rotation)
+    pub fn set_hue_rotation(&mut self, degrees_per_sec: f32) {
+        self.hue_rotation = degrees_per_sec;
+        if degrees_per_sec == 0.0 {
+            self.hue_offset = 0.0;
+        }
+    }
+
+    /// Dim toward `dim` (0.0 - 1.0 of normal brightness) after a few seconds below `threshold`
+    ///
+    /// A threshold of 0.0 disables idle dimming.
+    pub fn set_idle_behavior(&mut self, threshold: f32, dim: f32) {
+        self.idle_threshold = threshold.clamp(0.0, 1.0);
+        self.idle_dim = dim.clamp(0.0, 1.0);
+    }
+
+    /// Set the audio reactivity curve: amplitude is scaled by `base + audio^gamma * scale`
+    pub fn set_reactivity(&mut self, base: f32, scale: f32, gamma: f32) {
+        self.reactivity_base = base.clamp(0.0, 4.0);
+        self.reactivity_scale = scale.clamp(0.0, 8.0);
+        self.reactivity_gamma = gamma.clamp(0.1, 8.0);
+    }
+
+    /// Choose what drives the reactive amplitude: 0 broadband, 1 bass, 2 mid, 3 treble, 4 rms
+    pub fn set_reactivity_source(&mut self, source: u32) {
+        self.reactivity_source = match source {
+            1 => Some(ReactiveSource::Bass),
+            2 => Some(ReactiveSource::Mid),
+            3 => Some(ReactiveSource::Treble),
+            4 => Some(ReactiveSource::Rms),
+            _ => None,
+        };
+    }
+
+    /// Scale the reactive amplitude so the loudest recent passages reach near full scale
+    ///
+    /// Tracks a running RMS peak that rises quickly and decays over several seconds.
+    pub fn set_auto_gain(&mut self, enabled: bool) {
+        self.auto_gain = enabled;
+        self.rms_peak = AUTO_GAIN_FLOOR;
+    }
+
+    /// Listen for `duration_secs`, then tune the auto-gain and beat sensitivity to the audio
+    ///
+    /// Seeds the auto-gain's running peak with the loudest RMS heard and sets the beat
+    /// threshold from how much the bass fluctuates. Poll `is_calibrating` for a "listening" indicator.
+    pub fn start_calibration(&mut self, duration_secs: f32) {
+        let duration = duration_secs.clamp(0.1, 30.0);
+        self.calibration = Some(Calibration::new(duration));
+        log::info!("👂 Calibrating for {:.1}s...", duration);
+    }
+
+    /// Whether a calibration started with `start_calibration` is still listening
+    pub fn is_calibrating(&self) -> bool {
+        self.calibration.is_some()
+    }
+
+    /// Compress the reactive amplitude above `threshold` (0.0 - 1.0) by `ratio` (1.0 disables)
+    ///
+    /// Keeps loud masters from pinning the waves at full size. Defaults to 0.8 at 4:1.
+    pub fn set_compression(&mut self, threshold: f32, ratio: f32) {
+        self.compression_threshold = threshold.clamp(0.0, 1.0);
+        self.compression_ratio = ratio.clamp(1.0, 20.0);
+    }
+
+    /// Set how fast the spectrogram scrolls, in columns per second (0 freezes it)
+    pub fn set_spectrogram_speed(&mut self, columns_per_second: f32) {
+        self.spectrogram_speed = columns_per_second.clamp(0.0, 480.0);
+    }
+
+    /// Animate from a synthesized, time-driven signal instead of audio input
+    pub fn set_demo_mode(&mut self, enabled: bool) {
+        self.demo_mode = enabled;
+        log::info!("🎬 Demo mode {}", if enabled { "enabled" } else { "disabled" });
+    }
+
+    /// Render at reduced resolution for a pixelated look (1 = full resolution)
+    pub fn set_pixelate(&mut self, factor: u32) {
+        self.wave_params.pixelate = factor.clamp(1, 16);
+    }
+
+    /// Render the scene at `size * scale` and upscale it smoothly (0.25 - 2.0, default 1.0)
+    ///
+    /// A performance knob for weak GPUs, unlike the deliberately blocky `set_pixelate`.
+    /// Only the WGPU backend supports it.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        self.renderer_settings.render_scale = Some(scale);
+        if let Some(RenderBackend::Wgpu(ref mut renderer)) = self.renderer {
+            renderer.set_render_scale(scale);
+        }
+    }
+
+    /// Separate the RGB channels as the music gets louder (0.0 disables)
+    pub fn set_aberration(&mut self, intensity: f32) {
+        self.wave_params.aberration = intensity.clamp(0.0, 4.0);
+    }
+
+    /// Drive a parameter from an audio feature: `target += source * scale` every frame
+    ///
+    /// Sources: 0 bass, 1 mid, 2 treble, 3 RMS, 4 spectral centroid (all 0.0 - 1.0).
+    /// Targets: 0 amplitude, 1 frequency, 2 speed, 3 hue (degrees).
+    pub fn bind_reactive(&mut self, source: u32, target: u32, scale: f32) -> Result<(), OscError> {
+        let source = ReactiveSource::from_u32(source)
+            .ok_or_else(|| OscError::InvalidParam(format!("Unknown reactive source: {}", source)))?;
+        let target = ReactiveTarget::from_u32(target)
+            .ok_or_else(|| OscError::InvalidParam(format!("Unknown reactive target: {}", target)))?;
+        self.reactive_bindings.push(ReactiveBinding { source, target, scale });
+        Ok(())
+    }
+
+    /// Remove all reactive bindings
+    pub fn clear_reactive_bindings(&mut self) {
+        self.reactive_bindings.clear();
+    }
+
+    /// Forward the pointer position, normalized 0.0 - 1.0 from the top-left of the canvas
+    pub fn set_mouse(&mut self, x: f32, y: f32) {
+        self.wave_params.mouse = [x.clamp(0.0, 1.0), y.clamp(0.0, 1.0)];
+    }
+
+    /// Brighten louder wave regions toward white (0.0 disables)
+    pub fn set_intensity_response(&mut self, amount: f32) {
+        self.wave_params.intensity_from_amplitude = amount.clamp(0.0, 4.0);
+    }
+
+    /// Set mirror symmetry (0 none, 1 horizontal, 2 vertical, 3 quad)
+    pub fn set_symmetry(&mut self, mode: u32) {
+        self.wave_params.symmetry = mode.min(3);
+    }
+
+    /// Enable or disable polar (radial) presentation
+    pub fn set_polar(&mut self, enabled: bool) {
+        self.wave_params.polar = enabled;
+    }
+
+    /// Set the number of particles used by the particle field mode
+    pub fn set_particle_count(&mut self, n: u32) {
+        self.particle_count = n.clamp(1, 65536);
+        if let Some(RenderBackend::Wgpu(ref mut renderer)) = self.renderer {
+            renderer.set_particle_count(self.particle_count);
+        }
+    }
+
+    /// Replace the wave shader with WGSL `source` at runtime (development hot-reload)
+    ///
+    /// Compilation errors are returned and the current shader keeps running.
+    pub async fn reload_shader(&mut self, source: String) -> Result<(), OscError> {
+        match self.renderer {
+            Some(RenderBackend::Wgpu(ref mut renderer)) => renderer.reload_shader(&source).await,
+            _ => Err(OscError::Unsupported("Shader reload requires the WGPU renderer".to_string())),
+        }
+    }
+
+    /// Set the Bars3D grid: `cols` bars across the bands, `rows` frames of history deep (1 - 64 each)
+    pub fn set_bar_grid(&mut self, cols: u32, rows: u32) {
+        self.renderer_settings.bar_grid = Some((cols, rows));
+        if let Some(RenderBackend::Wgpu(ref mut renderer)) = self.renderer {
+            renderer.set_bar_grid(cols, rows);
+        }
+    }
+
+    /// Set the background color from sRGB components (0.0 - 1.0), e.g. a color picker value
+    pub fn set_background(&mut self, r: f32, g: f32, b: f32) {
+        self.renderer_settings.background = Some([r, g, b]);
+        if let Some(RenderBackend::Wgpu(ref mut renderer)) = self.renderer {
+            renderer.set_background(r, g, b);
+        }
+    }
+
+    /// Highlight one Bars3D column (band index across the grid) and dim the rest; -1 clears it
+    pub fn set_highlight_band(&mut self, band: i32) {
+        self.wave_params.highlight_band = band.max(-1);
+    }
+
+    /// Blend the scene over the background: 0 opaque, 1 alpha (default), 2 additive
+    pub fn set_blend(&mut self, mode: u32) -> Result<(), OscError> {
+        if mode > 2 {
+            return Err(OscError::InvalidParam(format!("Invalid blend mode: {}", mode)));
+        }
+        self.renderer_settings.blend = Some(mode);
+        match self.renderer {
+            Some(RenderBackend::Wgpu(ref mut renderer)) => renderer.set_blend(mode),
+            _ => Ok(()),
+        }
+    }
+
+    /// Draw the wave mesh as a wireframe where the GPU supports it
+    pub fn set_wireframe(&mut self, wireframe: bool) -> Result<(), OscError> {
+        self.renderer_settings.wireframe = wireframe;
+        match self.renderer {
+            Some(RenderBackend::Wgpu(ref mut renderer)) => renderer.set_polygon_mode(wireframe),
+            _ => Ok(()),
+        }
+    }
+
+    /// Draw into a `w x h` pixel region at `(x, y)` of the canvas, for multi-panel layouts
+    pub fn set_viewport(&mut self, x: f32, y: f32, w: f32, h: f32) -> Result<(), OscError> {
+        match self.renderer {
+            Some(RenderBackend::Wgpu(ref mut renderer)) => renderer.set_viewport(x, y, w, h),
+            _ => Err(OscError::Unsupported("Viewports require the WGPU renderer".to_string())),
+        }
+    }
+
+    /// Only write pixels inside the `w x h` rectangle at `(x, y)`, keeping the rest of the canvas
+    ///
+    /// The canvas texture starts cleared each frame, so every panel has to be redrawn each frame.
+    pub fn set_scissor(&mut self, x: u32, y: u32, w: u32, h: u32) -> Result<(), OscError> {
+        match self.renderer {
+            Some(RenderBackend::Wgpu(ref mut renderer)) => {
+                renderer.set_scissor(x, y, w, h);
+                Ok(())
+            }
+            _ => Err(OscError::Unsupported("Scissoring requires the WGPU renderer".to_string())),
+        }
+    }
+
+    /// Draw to the whole canvas again
+    pub fn reset_viewport(&mut self) {
+        if let Some(RenderBackend::Wgpu(ref mut renderer)) = self.renderer {
+            renderer.reset_viewport();
+        }
+    }
+
+    /// Set trace width: effective width = base + reactive * smoothed amplitude
+    pub fn set_line_width(&mut self, base: f32, reactive: f32) {
+        self.wave_params.line_width = base.clamp(0.005, 0.5);
+        self.wave_params.line_width_reactive = reactive.clamp(0.0, 0.5);
+    }
+
+    /// Set output exposure (0.1 - 4.0)
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.wave_params.exposure = exposure.clamp(0.1, 4.0);
+    }
+
+    /// Set output gamma (1.0 - 3.0)
+    pub fn set_gamma(&mut self, gamma: f32) {
+        self.wave_params.gamma = gamma.clamp(1.0, 3.0);
+    }
+
+    /// Show or hide the graticule overlay with the given cell count
+    pub fn set_grid(&mut self, enabled: bool, divisions: u32) {
+        self.wave_params.grid = if enabled { divisions.clamp(1, 64) } else { 0 };
+    }
+
+    /// Apply a named color palette (see `list_palettes`)
+    pub fn set_palette(&mut self, name: &str) -> Result<(), OscError> {
+        let palette = Palette::from_name(name).ok_or_else(|| {
+            OscError::InvalidParam(format!(
+                "Unknown palette '{}', expected one of: {}",
+                name,
+                Self::list_palettes().join(", ")
+            ))
+        })?;
+
+        let colors = palette.colors();
+        self.wave_params.hue = colors.hue;
+        self.wave_params.hue2 = colors.hue2;
+        self.wave_params.saturation = colors.saturation;
+        self.wave_params.lightness = colors.lightness;
+        log::info!("🎨 Palette changed to: {}", palette.name());
+        Ok(())
+    }
+
+    /// Available wave modes as an array of `{ id, name }` objects
+    pub fn list_modes() -> Result<JsValue, JsValue> {
+        let modes = js_sys::Array::new();
+        for mode in WaveMode::ALL {
+            let entry = js_sys::Object::new();
+            js_sys::Reflect::set(&entry, &"id".into(), &(mode as u32).into())?;
+            js_sys::Reflect::set(&entry, &"name".into(), &mode.name().into())?;
+            modes.push(&entry);
+        }
+        Ok(modes.into())
+    }
+
+    /// Device limits and capabilities as a plain object, or `null` without the WGPU renderer
+    ///
+    /// Lets the UI cap mesh resolution or particle counts and hide modes the hardware can't run.
+    pub fn get_limits(&self) -> Result<JsValue, JsValue> {
+        let Some(RenderBackend::Wgpu(ref renderer)) = self.renderer else {
+            return Ok(JsValue::NULL);
+        };
+        let limits = renderer.limits();
+        let downlevel = renderer.downlevel_flags();
+        let numbers = [
+            ("maxTextureDimension2D", limits.max_texture_dimension_2d as f64),
+            ("maxBufferSize", limits.max_buffer_size as f64),
+            ("maxUniformBufferBindingSize", limits.max_uniform_buffer_binding_size as f64),
+            ("maxStorageBuffersPerShaderStage", limits.max_storage_buffers_per_shader_stage as f64),
+            ("maxVertexBuffers", limits.max_vertex_buffers as f64),
+            ("maxVertexAttributes", limits.max_vertex_attributes as f64),
+            ("msaaSamples", renderer.msaa_samples() as f64),
+        ];
+        let flags = [
+            ("storageBuffers", downlevel.contains(wgpu::DownlevelFlags::VERTEX_STORAGE)),
+            ("computeShaders", downlevel.contains(wgpu::DownlevelFlags::COMPUTE_SHADERS)),
+            ("wireframe", renderer.features().contains(wgpu::Features::POLYGON_MODE_LINE)),
+        ];
+
+        let object = js_sys::Object::new();
+        for (key, value) in numbers {
+            js_sys::Reflect::set(&object, &key.into(), &value.into())?;
+        }
+        for (key, value) in flags {
+            js_sys::Reflect::set(&object, &key.into(), &value.into())?;
+        }
+        let modes = js_sys::Array::new();
+        for mode in WaveMode::ALL.into_iter().filter(|&mode| renderer.supports_mode(mode)) {
+            modes.push(&(mode as u32).into());
+        }
+        js_sys::Reflect::set(&object, &"supportedModes".into(), &modes)?;
+        Ok(object.into())
+    }
+
+    /// Names of the available color palettes
+    pub fn list_palettes() -> Vec<String> {
+        Palette::ALL.iter().map(|palette| palette.name().to_string()).collect()
+    }
+
+    /// Kick random frequency and speed offsets of up to these amounts on sharp transients
+    ///
+    /// Transients are detected from the spectral flux; each kick decays over a few frames.
+    /// Both 0.0 disables it. This consumes `AudioData::get_spectral_flux` once per frame.
+    pub fn set_transient_jitter(&mut self, freq_amount: f32, speed_amount: f32) {
+        self.jitter.frequency_amount = freq_amount.clamp(0.0, 5.0);
+        self.jitter.speed_amount = speed_amount.clamp(0.0, 5.0);
+    }
+
+    /// Set camera shake intensity on amplitude onsets (0.0 disables)
+    pub fn set_shake(&mut self, intensity: f32) {
+        self.shake.intensity = intensity.clamp(0.0, 0.5);
+    }
+
+    /// Lock the oscilloscope trace to a `level` crossing with the given slope
+    pub fn set_trigger(&mut self, level: f32, rising: bool) {
+        self.trigger = Some((level.clamp(-1.0, 1.0), rising));
+    }
+
+    /// Let the oscilloscope trace free-run from the start of the buffer
+    pub fn clear_trigger(&mut self) {
+        self.trigger = None;
+    }
+
+    /// Get up to `num_samples` of the time domain data, starting at the trigger point
+    pub fn get_waveform(&self, num_samples: usize) -> Vec<f32> {
+        if let Ok(audio) = self.audio_data.lock() {
+            let offset = self
+                .trigger
+                .map_or(0, |(level, rising)| audio.find_trigger(level, rising));
+            audio.time_domain_data[offset..].iter().take(num_samples).copied().collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Set phosphor persistence decay (0.0 clears every frame, higher leaves longer trails)
+    pub fn set_persistence(&mut self, decay: f32) {
+        self.wave_params.persistence = decay.clamp(0.0, 0.98);
+    }
+
+    /// Color the trace by frequency (bass red → treble blue) instead of the global hue
+    pub fn set_color_by_frequency(&mut self, enabled: bool) {
+        self.wave_params.color_by_frequency = enabled;
+    }
+
+    /// Set the number of stacked sine layers in SineWaves mode (1-8)
+    pub fn set_layers(&mut self, layers: u32) {
+        self.wave_params.layers = layers.clamp(1, wave::MAX_LAYERS);
+    }
+
+    /// Override the render time (seconds) used by the next `render`
+    pub fn set_time(&mut self, seconds: f32) {
+        self.manual_time = Some(seconds);
+    }
+
+    /// Render every frame at the time set by `set_time` instead of the timestamp
+    pub fn use_fixed_time(&mut self, enabled: bool) {
+        self.use_fixed_time = enabled;
+    }
+
+    /// Sample the current wave over a `resolution x resolution` grid for external renderers
+    ///
+    /// Returns interleaved (x, height, z) positions; see `generate_mesh_indices`.
+    pub fn generate_mesh(&self, resolution: u32, time: f32) -> Vec<f32> {
+        wave::wave_mesh(resolution.min(1024), time, &self.wave_params)
+    }
+
+    /// Estimated tempo (BPM) from recent beat intervals, 0.0 until a few beats are detected
+    pub fn get_bpm(&self) -> f32 {
+        self.beat_detector.get_bpm()
+    }
+
+    /// Flash the scene in time with the estimated tempo, staying on the beat grid through quiet bars
+    ///
+    /// Has no visible effect until enough beats are detected to estimate a BPM.
+    pub fn set_tempo_pulse(&mut self, enabled: bool) {
+        self.tempo_pulse = enabled;
+    }
+
+    /// Position through the current beat (0.0 on the beat - 1.0), or 0.0 while the tempo is unknown
+    pub fn get_pulse_phase(&self) -> f32 {
+        self.beat_detector.pulse_phase(self.time).unwrap_or(0.0)
+    }
+
+    /// Get all derived audio metrics from the current audio snapshot in one call
+    pub fn get_features(&mut self) -> AudioFeatures {
+        let beat = self.beat_detector.is_beat();
+        let Ok(audio) = self.audio_data.lock() else {
+            return AudioFeatures { beat, ..Default::default() };
+        };
+
+        let rms = audio.get_rms();
+        let bands = audio.get_frequency_bands(NUM_BANDS);
+        let inputs = ReactiveInputs::new(&bands, rms, 0.0);
+        AudioFeatures {
+            amplitude: audio.get_amplitude(),
+            rms,
+            bass: inputs.bass,
+            mid: inputs.mid,
+            treble: inputs.treble,
+            centroid: audio.get_spectral_centroid(),
+            peak_frequency: audio.get_peak_frequency(),
+            beat,
+        }
+    }
+
+    /// Get a copy of the current wave parameters
+    pub fn get_params(&self) -> WaveParams {
+        self.wave_params.clone()
+    }
+
+    /// Get the current visualization mode
+    pub fn get_mode(&self) -> WaveMode {
+        self.wave_params.mode
+    }
+
+    /// Get the base wave amplitude (before audio reactivity)
+    pub fn get_amplitude(&self) -> f32 {
+        self.wave_params.amplitude
+    }
+
+    /// Get the wave frequency
+    pub fn get_frequency(&self) -> f32 {
+        self.wave_params.frequency
+    }
+
+    /// Get the wave speed
+    pub fn get_speed(&self) -> f32 {
+        self.wave_params.speed
+    }
+
+    /// Get the current hue (0-360), including any automatic rotation
+    pub fn get_hue(&self) -> f32 {
+        palette::wrap_hue(self.wave_params.hue + self.hue_offset)
+    }
+
+    /// Render a single frame
+    pub fn render(&mut self, timestamp: f64) -> Result<(), OscError> {
+        let time = if self.use_fixed_time {
+            self.manual_time.unwrap_or(self.time)
+        } else {
+            match self.manual_time.take() {
+                Some(time) => time,
+                None => ((timestamp - self.start_time) / 1000.0) as f32,
+            }
+        };
+        self.render_at(time)
+    }
+
+    /// Advance the visualizer clock by `dt_secs` and render, for loops without a timestamp
+    ///
+    /// Suits fixed-timestep loops, workers and native hosts; `render` remains the
+    /// `requestAnimationFrame` path.
+    pub fn render_dt(&mut self, dt_secs: f32) -> Result<(), OscError> {
+        if !dt_secs.is_finite() || dt_secs < 0.0 {
+            return Err(OscError::InvalidParam(format!("Invalid delta time: {}", dt_secs)));
+        }
+        self.render_at(self.time + dt_secs)
+    }
+
+    /// Render a single frame at exactly `time_secs`, ignoring the start time and `set_time`
+    ///
+    /// Uses the current audio snapshot; pair with frame capture to export frame `i` at `i / fps`.
+    pub fn render_at(&mut self, time_secs: f32) -> Result<(), OscError> {
+        let (params, bands) = self.prepare_frame(time_secs);
+
+        if let Some(ref mut renderer) = self.renderer {
+            renderer.render(time_secs, &params, &bands)?;
+        }
+        self.notify_beat();
         Ok(())
     }
+
+    /// Start recording frames at a fixed rate of `fps`
+    pub fn start_recording(&mut self, fps: u32) {
+        let fps = fps.clamp(1, 240);
+        self.recording = Some(Recording {
+            fps,
+            start_time: self.time,
+            frame_count: 0,
+            frames: Vec::new(),
+        });
+        log::info!("⏺️ Recording started at {} fps", fps);
+    }
+
+    /// Render the next recorded frame offscreen, advancing time by `1 / fps`
+    ///
+    /// Fails once the recording would exceed its size cap; the frames so far stay
+    /// available from `stop_recording`.
+    pub async fn record_frame(&mut self) -> Result<(), OscError> {
+        let time = match self.recording {
+            Some(ref recording) => recording.start_time + recording.frame_count as f32 / recording.fps as f32,
+            None => return Err(OscError::InvalidState("Not recording".to_string())),
+        };
+        let (params, bands) = self.prepare_frame(time);
+
+        let renderer = match self.renderer {
+            Some(RenderBackend::Wgpu(ref mut renderer)) => renderer,
+            _ => return Err(OscError::Unsupported("Recording requires the WGPU renderer".to_string())),
+        };
+        let pixels = renderer.capture_frame(time, &params, &bands).await?;
+
+        if let Some(ref mut recording) = self.recording {
+            if recording.frames.len() + pixels.len() > MAX_RECORDING_BYTES {
+                log::warn!("⚠️ Recording full after {} frames", recording.frame_count);
+                return Err(OscError::InvalidState(format!(
+                    "Recording limit of {} bytes reached, stop the recording",
+                    MAX_RECORDING_BYTES
+                )));
+            }
+            recording.frames.extend_from_slice(&pixels);
+            recording.frame_count += 1;
+        }
+        Ok(())
+    }
+
+    /// Read back the RGBA color of the last rendered frame at pixel `(x, y)`
+    ///
+    /// Returns 4 bytes; `(0, 0)` is the top-left corner of the canvas.
+    pub async fn sample_pixel(&mut self, x: u32, y: u32) -> Result<Vec<u8>, OscError> {
+        match self.renderer {
+            Some(RenderBackend::Wgpu(ref mut renderer)) => Ok(renderer.read_pixel(x, y).await?.to_vec()),
+            _ => Err(OscError::Unsupported("Pixel sampling requires the WGPU renderer".to_string())),
+        }
+    }
+
+    /// Stop recording and return the raw RGBA frames, back to back at canvas size
+    pub fn stop_recording(&mut self) -> Vec<u8> {
+        match self.recording.take() {
+            Some(recording) => {
+                log::info!("⏹️ Recording stopped after {} frames", recording.frame_count);
+                recording.frames
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Resize the canvas
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), OscError> {
+        if let Some(ref mut renderer) = self.renderer {
+            renderer.resize(width, height)?;
+        }
+        Ok(())
+    }
+
+    /// Release the renderer and its GPU resources
+    ///
+    /// Afterwards `render` and `resize` are no-ops; call `init` again to reuse the instance.
+    pub fn destroy(&mut self) {
+        self.renderer = None;
+        self.recording = None;
+        log::info!("🧹 Visualizer destroyed");
+    }
+}
+
+impl Visualizer {
+    /// A Visualizer with default settings and no renderer, timed from `start_time` (ms)
+    fn with_start_time(start_time: f64) -> Visualizer {
+        Visualizer {
+            renderer: None,
+            audio_data: Arc::new(Mutex::new(AudioData::new(2048))),
+            wave_params: WaveParams::default(),
+            start_time,
+            time: 0.0,
+            transition: None,
+            transition_easing: Easing::Linear,
+            particle_count: DEFAULT_PARTICLE_COUNT,
+            renderer_settings: RendererSettings::default(),
+            smoothed_amplitude: 0.0,
+            recording: None,
+            shake: CameraShake::default(),
+            jitter: TransientJitter::default(),
+            trigger: None,
+            manual_time: None,
+            use_fixed_time: false,
+            hue_rotation: 0.0,
+            hue_offset: 0.0,
+            idle_threshold: 0.0,
+            idle_dim: 1.0,
+            quiet_time: 0.0,
+            idle_level: 0.0,
+            reactivity_base: 0.5,
+            reactivity_scale: 1.5,
+            reactivity_gamma: 1.0,
+            reactivity_source: None,
+            compression_threshold: 0.8,
+            compression_ratio: 4.0,
+            spectrogram_speed: 60.0,
+            spectrogram_progress: 0.0,
+            clamp_enabled: true,
+            demo_mode: false,
+            reactive_bindings: Vec::new(),
+            beat_detector: BeatDetector::default(),
+            audio_frozen: false,
+            tempo_pulse: false,
+            auto_gain: false,
+            rms_peak: AUTO_GAIN_FLOOR,
+            calibration: None,
+            beat_callback: None,
+            in_beat_callback: false,
+        }
+    }
+
+    /// Apply the particle count and renderer settings made so far to a new renderer
+    #[cfg(target_arch = "wasm32")]
+    fn configure_renderer(&self, renderer: &mut Renderer) {
+        let settings = &self.renderer_settings;
+        renderer.set_particle_count(self.particle_count);
+        if let Some(scale) = settings.render_scale {
+            renderer.set_render_scale(scale);
+        }
+        if let Some((cols, rows)) = settings.bar_grid {
+            renderer.set_bar_grid(cols, rows);
+        }
+        if let Some([r, g, b]) = settings.background {
+            renderer.set_background(r, g, b);
+        }
+        if let Some(mode) = settings.blend {
+            if let Err(e) = renderer.set_blend(mode) {
+                log::warn!("⚠️ Couldn't restore blend mode: {}", e);
+            }
+        }
+        if let Err(e) = renderer.set_polygon_mode(settings.wireframe) {
+            log::warn!("⚠️ Couldn't restore wireframe: {}", e);
+        }
+    }
+
+    /// Advance transitions and audio smoothing to `time`, returning the params and bands to draw
+    fn prepare_frame(&mut self, time: f32) -> (WaveParams, Vec<f32>) {
+        let dt = time - self.time;
+        self.time = time;
+
+        if let Some(transition) = self.transition {
+            let progress = transition.progress(time);
+            if progress >= 1.0 {
+                self.wave_params.mode = transition.to_mode;
+                self.wave_params.mix = 0.0;
+                self.transition = None;
+            } else {
+                self.wave_params.mode = transition.from_mode;
+                self.wave_params.target_mode = transition.to_mode;
+                self.wave_params.mix = self.transition_easing.apply(progress);
+            }
+        } else {
+            self.wave_params.mix = 0.0;
+        }
+
+        let needs_features =
+            !self.reactive_bindings.is_empty()
+                || self.reactivity_source.is_some()
+                || self.auto_gain
+                || self.calibration.is_some();
+        let spectrogram_visible = self.wave_params.mode == WaveMode::Spectrogram
+            || (self.wave_params.mix > 0.0 && self.wave_params.target_mode == WaveMode::Spectrogram);
+        let spectrogram_columns = if spectrogram_visible { self.spectrogram_columns(dt) } else { 0 };
+        let goniometer_visible = self.wave_params.mode == WaveMode::Goniometer
+            || (self.wave_params.mix > 0.0 && self.wave_params.target_mode == WaveMode::Goniometer);
+        let AudioSnapshot { amplitude, bands, rms, centroid, flux, spectrum, goniometer } =
+            self.snapshot_audio(time, needs_features, spectrogram_columns > 0, goniometer_visible);
+
+        let camera_offset = self.shake.update(dt, amplitude, self.smoothed_amplitude);
+        let (frequency_jitter, speed_jitter) = self.jitter.update(dt, flux);
+        self.hue_offset = palette::wrap_hue(self.hue_offset + self.hue_rotation * dt);
+        self.smoothed_amplitude += (amplitude - self.smoothed_amplitude) * 0.2;
+        self.update_idle(dt, amplitude);
+        let bass = ReactiveInputs::new(&bands, rms, centroid).bass;
+        self.update_calibration(dt, rms, bass);
+        self.beat_detector.update(bass, time);
+
+        if let Some(RenderBackend::Wgpu(ref mut renderer)) = self.renderer {
+            if let Some(spectrum) = spectrum {
+                for _ in 0..spectrogram_columns {
+                    renderer.push_spectrogram_column(&spectrum);
+                }
+            }
+            if let Some(points) = goniometer {
+                renderer.update_goniometer(&points);
+            }
+        }
+
+        // Apply audio reactivity to wave params
+        let mut params = self.wave_params.clone();
+        let mut reactive_amplitude = amplitude;
+        if needs_features {
+            let inputs = ReactiveInputs::new(&bands, rms, centroid);
+            for binding in &self.reactive_bindings {
+                binding.apply(&inputs, &mut params);
+            }
+            if let Some(source) = self.reactivity_source {
+                reactive_amplitude = inputs.level(source);
+            }
+        }
+        if self.auto_gain {
+            reactive_amplitude = (reactive_amplitude * self.update_auto_gain(dt, rms)).min(1.0);
+        }
+        let reactive_amplitude = soft_knee(reactive_amplitude, self.compression_threshold, self.compression_ratio);
+        params.camera_offset = camera_offset;
+        params.frequency += frequency_jitter;
+        params.speed += speed_jitter;
+        params.pulse = if self.tempo_pulse { self.beat_detector.pulse_phase(time) } else { None };
+        params.hue = palette::wrap_hue(params.hue + self.hue_offset);
+        params.amplitude *= self.reactivity_base + reactive_amplitude.powf(self.reactivity_gamma) * self.reactivity_scale;
+        params.line_width += params.line_width_reactive * self.smoothed_amplitude;
+        params.aberration = (params.aberration * self.smoothed_amplitude).min(1.0) * MAX_ABERRATION;
+
+        let idle_scale = 1.0 + (self.idle_dim - 1.0) * self.idle_level;
+        params.amplitude *= idle_scale;
+        params.exposure *= idle_scale;
+
+        (params, bands)
+    }
+
+    /// Invoke the beat callback if the last frame was a beat
+    ///
+    /// Exceptions thrown by the callback are logged, not propagated to `render`.
+    fn notify_beat(&mut self) {
+        if !self.beat_detector.is_beat() || self.in_beat_callback {
+            return;
+        }
+        // Clone so the callback may replace or clear itself while running
+        let Some(callback) = self.beat_callback.clone() else {
+            return;
+        };
+        self.in_beat_callback = true;
+        let amplitude = JsValue::from_f64(self.smoothed_amplitude as f64);
+        if let Err(e) = callback.call1(&JsValue::NULL, &amplitude) {
+            log::warn!("⚠️ Beat callback threw: {:?}", e);
+        }
+        self.in_beat_callback = false;
+    }
+
+    /// Resolve `mode`, refusing modes the active renderer can't display
+    fn supported_mode(&self, mode: u32) -> Result<WaveMode, OscError> {
+        let mode = WaveMode::from_u32(mode);
+        match self.renderer {
+            Some(ref renderer) if !renderer.supports_mode(mode) => Err(OscError::Unsupported(format!(
+                "Wave mode {:?} is not supported by this GPU",
+                mode
+            ))),
+            _ => Ok(mode),
+        }
+    }
+
+    /// Validate a setter value: `None` for NaN or infinity, clamped unless clamping is disabled
+    fn limit(&self, value: f32, min: f32, max: f32) -> Option<f32> {
+        if !value.is_finite() {
+            None
+        } else if self.clamp_enabled {
+            Some(value.clamp(min, max))
+        } else {
+            Some(value)
+        }
+    }
+
+    /// Whole spectrogram columns due after `dt` seconds at the current scroll speed
+    fn spectrogram_columns(&mut self, dt: f32) -> u32 {
+        self.spectrogram_progress += dt.max(0.0) * self.spectrogram_speed;
+        let columns = self.spectrogram_progress.floor();
+        self.spectrogram_progress -= columns;
+        (columns as u32).min(SPECTROGRAM_COLUMNS)
+    }
+
+    /// Read everything the frame needs from the audio, locking it at most once
+    ///
+    /// Demo mode synthesizes the levels instead and leaves the goniometer untouched.
+    fn snapshot_audio(&self, time: f32, needs_features: bool, spectrum: bool, goniometer: bool) -> AudioSnapshot {
+        if self.demo_mode {
+            let (amplitude, bands) = demo_signal(time);
+            return AudioSnapshot {
+                amplitude,
+                spectrum: spectrum.then(|| bands.clone()),
+                bands,
+                rms: amplitude,
+                centroid: 0.5,
+                flux: 0.0,
+                goniometer: None,
+            };
+        }
+
+        match self.audio_data.lock() {
+            Ok(mut audio) => {
+                let (rms, centroid) = if needs_features {
+                    (audio.get_rms(), audio.get_normalized_centroid())
+                } else {
+                    (0.0, 0.0)
+                };
+                AudioSnapshot {
+                    amplitude: audio.get_amplitude(),
+                    bands: audio.get_frequency_bands(NUM_BANDS),
+                    rms,
+                    centroid,
+                    flux: if self.jitter.is_enabled() { audio.get_spectral_flux() } else { 0.0 },
+                    spectrum: spectrum.then(|| audio.get_frequency_bands(SPECTROGRAM_BANDS)),
+                    goniometer: goniometer.then(|| audio.get_goniometer_points(GONIOMETER_POINTS)),
+                }
+            }
+            Err(_) => AudioSnapshot {
+                amplitude: 0.0,
+                bands: vec![0.0; NUM_BANDS],
+                rms: 0.0,
+                centroid: 0.0,
+                flux: 0.0,
+                spectrum: None,
+                goniometer: None,
+            },
+        }
+    }
+
+    /// Feed the running calibration and apply its result once the window ends
+    fn update_calibration(&mut self, dt: f32, rms: f32, bass: f32) {
+        let Some(ref mut calibration) = self.calibration else {
+            return;
+        };
+        if let Some(result) = calibration.update(dt, rms, bass) {
+            self.calibration = None;
+            self.rms_peak = result.rms_peak.max(AUTO_GAIN_FLOOR);
+            self.beat_detector.set_sensitivity(result.beat_sensitivity);
+            log::info!(
+                "✅ Calibrated: RMS peak {:.3}, beat sensitivity {:.2}",
+                result.rms_peak, result.beat_sensitivity
+            );
+        }
+    }
+
+    /// Follow the RMS peak (fast attack, slow release) and return the gain to apply
+    fn update_auto_gain(&mut self, dt: f32, rms: f32) -> f32 {
+        let dt = dt.max(0.0);
+        if rms > self.rms_peak {
+            self.rms_peak += (rms - self.rms_peak) * (1.0 - (-dt / AUTO_GAIN_ATTACK_SECS).exp());
+        } else {
+            self.rms_peak *= (-dt / AUTO_GAIN_RELEASE_SECS).exp();
+        }
+        self.rms_peak = self.rms_peak.max(AUTO_GAIN_FLOOR);
+        AUTO_GAIN_TARGET / self.rms_peak
+    }
+
+    /// Track silence and fade into (or snap out of) the idle state
+    fn update_idle(&mut self, dt: f32, amplitude: f32) {
+        if amplitude >= self.idle_threshold {
+            self.quiet_time = 0.0;
+            self.idle_level = 0.0;
+            return;
+        }
+
+        self.quiet_time += dt.max(0.0);
+        if self.quiet_time > IDLE_DELAY_SECS {
+            self.idle_level = (self.idle_level + dt.max(0.0) / IDLE_FADE_SECS).min(1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frequency_bands_upsample_without_nan() {
+        let mut audio = AudioData::new(2048);
+        let spectrum: Vec<f32> = (0..1024).map(|i| -100.0 + i as f32 * 100.0 / 1023.0).collect();
+        audio.set_frequency_data(&spectrum);
+
+        let bands = audio.get_frequency_bands(4096);
+        assert_eq!(bands.len(), 4096);
+        assert!(bands.iter().all(|band| band.is_finite()));
+        assert!(bands[0].abs() < 1e-6);
+        assert!((bands[4095] - 1.0).abs() < 1e-6);
+        assert!(bands.windows(2).all(|pair| pair[1] >= pair[0] - 1e-6));
+    }
+
+    #[test]
+    fn interpolated_spectrum_passes_through_bins() {
+        let mut audio = AudioData::new(4);
+        audio.set_frequency_data(&[-100.0, 0.0]);
+        let bins = audio.get_normalized_spectrum();
+
+        let points = audio.get_interpolated_spectrum(5);
+        assert_eq!(points.len(), 5);
+        assert!((points[0] - bins[0]).abs() < 1e-6);
+        assert!((points[2] - (bins[0] + bins[1]) * 0.5).abs() < 1e-6);
+        assert!((points[4] - bins[1]).abs() < 1e-6);
+    }
+
+    #[test]
+    fn eq_scales_normalized_bins() {
+        let mut audio = AudioData::new(8);
+        audio.set_frequency_data(&[-50.0; 4]);
+        audio.set_eq(&[0.0, 2.0]).unwrap();
+        let bins = audio.get_normalized_spectrum();
+        assert!(bins[0].abs() < 1e-6);
+        assert!((bins[3] - 1.0).abs() < 1e-6);
+        assert!(bins.windows(2).all(|pair| pair[1] >= pair[0]));
+
+        // A flat curve is the same as no EQ
+        audio.set_eq(&[1.0, 1.0, 1.0]).unwrap();
+        assert!(audio.get_normalized_spectrum().iter().all(|&bin| (bin - 0.5).abs() < 1e-6));
+        assert!(audio.set_eq(&[-1.0]).is_err());
+    }
+
+    #[test]
+    fn non_finite_input_is_sanitized() {
+        let mut audio = AudioData::new(64);
+        audio.set_frequency_data(&[f32::NEG_INFINITY; 32]);
+        let amplitude = audio.get_amplitude();
+        assert!(amplitude.is_finite() && (0.0..=1.0).contains(&amplitude));
+        assert!(audio.get_frequency_bands(8).iter().all(|band| band.is_finite()));
+
+        let mut samples = [0.5; 64];
+        samples[3] = f32::NAN;
+        samples[7] = f32::INFINITY;
+        audio.set_time_domain_data(&samples);
+        assert!(audio.get_rms().is_finite());
+        assert!(audio.perform_fft(&samples));
+        assert!(audio.get_raw_spectrum().iter().all(|db| db.is_finite()));
+    }
+
+    #[test]
+    fn fft_rejects_non_power_of_two_sizes() {
+        let mut audio = AudioData::new(1000);
+        let before = audio.get_raw_spectrum();
+        assert!(!audio.perform_fft(&[0.5; 1000]));
+        assert_eq!(audio.get_raw_spectrum(), before);
+
+        let mut audio = AudioData::new(1024);
+        assert!(audio.perform_fft(&[0.5; 1024]));
+    }
+
+    #[test]
+    fn renderer_settings_are_kept_without_a_renderer() {
+        let mut vis = Visualizer::with_start_time(0.0);
+        vis.set_render_scale(0.5);
+        vis.set_bar_grid(16, 8);
+        vis.set_background(0.5, 0.25, 0.75);
+        vis.set_blend(2).unwrap();
+        vis.set_wireframe(true).unwrap();
+        assert!(matches!(vis.set_blend(3), Err(OscError::InvalidParam(_))));
+
+        vis.destroy();
+        let settings = &vis.renderer_settings;
+        assert_eq!(settings.render_scale, Some(0.5));
+        assert_eq!(settings.bar_grid, Some((16, 8)));
+        assert_eq!(settings.background, Some([0.5, 0.25, 0.75]));
+        assert_eq!(settings.blend, Some(2));
+        assert!(settings.wireframe);
+    }
+
+    #[test]
+    fn trigger_finds_the_first_crossing() {
+        let mut audio = AudioData::new(64);
+        let sine: Vec<f32> =
+            (0..64).map(|i| (std::f32::consts::TAU * (i as f32 - 10.5) / 64.0).sin()).collect();
+        audio.set_time_domain_data(&sine);
+        assert_eq!(audio.find_trigger(0.0, true), 11);
+        assert_eq!(audio.find_trigger(0.0, false), 43);
+        assert_eq!(audio.find_trigger(1.5, true), 0);
+    }
+
+    #[test]
+    fn spectral_flux_only_counts_growth() {
+        let mut audio = AudioData::new(64);
+        audio.set_frequency_data(&[-50.0; 32]);
+        assert_eq!(audio.get_spectral_flux(), 0.0);
+        audio.set_frequency_data(&[-50.0; 32]);
+        assert_eq!(audio.get_spectral_flux(), 0.0);
+
+        audio.set_frequency_data(&[-20.0; 32]);
+        assert!((audio.get_spectral_flux() - 0.3).abs() < 1e-5);
+        audio.set_frequency_data(&[-80.0; 32]);
+        assert_eq!(audio.get_spectral_flux(), 0.0);
+    }
+
+    #[test]
+    fn peak_frequency_interpolates_the_loudest_bin() {
+        let mut audio = AudioData::new(64);
+        audio.set_sample_rate(6400.0).unwrap();
+        let mut spectrum = [-100.0; 32];
+        assert_eq!(audio.get_peak_frequency(), 0.0);
+
+        spectrum[9] = -30.0;
+        spectrum[10] = -10.0;
+        spectrum[11] = -30.0;
+        audio.set_frequency_data(&spectrum);
+        assert!((audio.get_peak_frequency() - 1000.0).abs() < 1e-3);
+
+        spectrum[11] = -20.0;
+        audio.set_frequency_data(&spectrum);
+        let peak = audio.get_peak_frequency();
+        assert!(peak > 1000.0 && peak < 1050.0, "{}", peak);
+    }
+
+    #[test]
+    fn soft_knee_is_monotonic_and_bounded() {
+        let curve: Vec<f32> = (0..=100).map(|i| soft_knee(i as f32 / 100.0, 0.5, 4.0)).collect();
+        assert!(curve.windows(2).all(|pair| pair[1] >= pair[0]));
+        assert!(curve.iter().all(|&level| level <= 1.0));
+        assert_eq!(curve[20], 0.2);
+        assert!((curve[100] - (0.5 + 0.5 / 4.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn auto_gain_converges_on_the_target() {
+        let mut vis = Visualizer::with_start_time(0.0);
+        vis.set_auto_gain(true);
+        let mut gain = 0.0;
+        for _ in 0..120 {
+            gain = vis.update_auto_gain(1.0 / 60.0, 0.3);
+        }
+        assert!((gain - AUTO_GAIN_TARGET / 0.3).abs() < 1e-3);
+
+        // Silence releases slowly and the floor caps the gain
+        let released = vis.update_auto_gain(1.0, 0.0);
+        assert!(released > gain && released < AUTO_GAIN_TARGET / 0.2);
+        for _ in 0..60 {
+            gain = vis.update_auto_gain(1.0, 0.0);
+        }
+        assert!((gain - AUTO_GAIN_TARGET / AUTO_GAIN_FLOOR).abs() < 1e-3);
+    }
+
+    #[test]
+    fn smoothing_blends_with_the_previous_frame() {
+        let mut audio = AudioData::new(8);
+        audio.set_frequency_data(&[-80.0; 4]);
+        audio.set_smoothing_constant(0.75);
+        for n in 1..=4 {
+            audio.set_frequency_data(&[-40.0; 4]);
+            let expected = -40.0 - 40.0 * 0.75_f32.powi(n);
+            assert!((audio.get_raw_spectrum()[0] - expected).abs() < 1e-4);
+        }
+
+        // Clamped below 1.0 so the data still moves
+        audio.set_smoothing_constant(1.5);
+        let before = audio.get_raw_spectrum()[0];
+        audio.set_frequency_data(&[0.0; 4]);
+        assert!(audio.get_raw_spectrum()[0] > before);
+    }
+
+    #[test]
+    fn stereo_meters_handle_edge_cases() {
+        // Before any right channel data
+        let mut audio = AudioData::new(4);
+        audio.set_time_domain_data(&[0.5, -0.5, 0.25, -0.25]);
+        assert_eq!(audio.get_correlation(), 0.0);
+        assert_eq!(audio.get_balance(), 0.0);
+
+        // A silent channel
+        audio.set_right_time_domain_data(&[0.0; 4]);
+        assert_eq!(audio.get_correlation(), 0.0);
+        assert_eq!(audio.get_balance(), 0.0);
+
+        audio.set_right_time_domain_data(&[1.5, -1.5, 0.75, -0.75]);
+        assert!((audio.get_correlation() - 1.0).abs() < 1e-6);
+        assert!((audio.get_balance() - 0.5).abs() < 1e-6);
+
+        // A single sample
+        let mut audio = AudioData::new(1);
+        audio.set_time_domain_data(&[0.5]);
+        audio.set_right_time_domain_data(&[-0.5]);
+        assert!((audio.get_correlation() + 1.0).abs() < 1e-6);
+        assert_eq!(audio.get_balance(), 0.0);
+    }
+
+    #[test]
+    fn band_count_fixes_get_bands() {
+        let mut audio = AudioData::new(64);
+        let spectrum: Vec<f32> = (0..32).map(|i| -100.0 + i as f32 * 3.0).collect();
+        audio.set_frequency_data(&spectrum);
+
+        assert_eq!(audio.get_bands().len(), NUM_BANDS);
+        audio.set_band_count(4).unwrap();
+        assert_eq!(audio.get_bands(), audio.get_frequency_bands(4));
+        assert!(audio.set_band_count(0).is_err());
+        assert_eq!(audio.band_count(), 4);
+    }
+
+    #[test]
+    fn goniometer_rotates_stereo_pairs() {
+        let mut audio = AudioData::new(4);
+        audio.set_time_domain_data(&[0.5, -0.5, 0.25, 0.0]);
+
+        // Mono (no right channel) stays on the vertical mid axis
+        let mono = audio.get_goniometer_points(4);
+        assert_eq!(mono.len(), 8);
+        assert!(mono.chunks(2).all(|point| point[0] == 0.0));
+        assert!((mono[1] - 0.5 * std::f32::consts::SQRT_2).abs() < 1e-6);
+
+        // Inverted right channel lies on the horizontal side axis
+        audio.set_right_time_domain_data(&[-0.5, 0.5, -0.25, 0.0]);
+        let inverted = audio.get_goniometer_points(2);
+        assert_eq!(inverted.len(), 4);
+        assert!(inverted.chunks(2).all(|point| point[1] == 0.0));
+        assert!((inverted[0] + 0.5 * std::f32::consts::SQRT_2).abs() < 1e-6);
+    }
+
+    #[test]
+    fn waveform_envelope_brackets_each_column() {
+        let mut audio = AudioData::new(8);
+        audio.set_time_domain_data(&[0.1, -0.4, 0.3, 0.2, -0.9, 0.5, 0.0, 0.7]);
+
+        assert_eq!(audio.get_waveform_envelope(2), vec![-0.4, 0.3, -0.9, 0.7]);
+        // More columns than samples repeat each sample as a flat column
+        let dense = audio.get_waveform_envelope(16);
+        assert_eq!(dense.len(), 32);
+        assert_eq!(&dense[..4], &[0.1, 0.1, 0.1, 0.1]);
+        assert_eq!(AudioData::new(0).get_waveform_envelope(3), vec![0.0; 6]);
+    }
+
+    #[test]
+    fn interpolated_spectrum_is_zero_when_empty() {
+        let audio = AudioData::new(0);
+        assert_eq!(audio.get_interpolated_spectrum(3), vec![0.0; 3]);
+    }
+
+    /// Drive a future to completion on the test thread (wgpu's native futures resolve on poll)
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        struct ThreadWaker(std::thread::Thread);
+        impl std::task::Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+        let waker = Arc::new(ThreadWaker(std::thread::current())).into();
+        let mut context = std::task::Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut context) {
+                return output;
+            }
+            std::thread::park_timeout(std::time::Duration::from_millis(10));
+        }
+    }
+
+    /// Headless renderer on the platform's wgpu backend, or None (skipping the test) without a GPU
+    fn headless_renderer(width: u32, height: u32) -> Option<Renderer> {
+        headless_renderer_with_format(width, height, wgpu::TextureFormat::Rgba8Unorm)
+    }
+
+    fn headless_renderer_with_format(width: u32, height: u32, format: wgpu::TextureFormat) -> Option<Renderer> {
+        match block_on(Renderer::new_headless(width, height, format)) {
+            Ok(renderer) => Some(renderer),
+            Err(OscError::AdapterUnavailable) => {
+                eprintln!("No wgpu adapter, skipping headless test");
+                None
+            }
+            Err(e) => panic!("Headless renderer failed: {}", e),
+        }
+    }
+
+    /// Row of the brightest green value in column `x` of an RGBA frame
+    fn brightest_row(frame: &[u8], width: usize, height: usize, x: usize) -> usize {
+        (0..height).max_by_key(|&y| frame[(y * width + x) * 4 + 1]).unwrap()
+    }
+
+    #[test]
+    fn headless_sine_waves_draw_the_trace() {
+        let (width, height) = (64, 32);
+        let Some(mut renderer) = headless_renderer(width as u32, height as u32) else {
+            return;
+        };
+        let bands = [0.0; NUM_BANDS];
+
+        // Without amplitude the trace lies flat on the center line
+        let flat = WaveParams { amplitude: 0.0, ..WaveParams::default() };
+        renderer.render(0.0, &flat, &bands).unwrap();
+        let frame = block_on(renderer.read_frame()).unwrap();
+        assert_eq!(frame.len(), width * height * 4);
+        for x in 0..width {
+            let row = brightest_row(&frame, width, height, x);
+            assert!((height / 2 - 1..=height / 2).contains(&row), "column {} peaks at row {}", x, row);
+        }
+
+        // The default wave bends away from it, over a dark background
+        renderer.render(0.0, &WaveParams::default(), &bands).unwrap();
+        let frame = block_on(renderer.read_frame()).unwrap();
+        let rows: Vec<usize> = (0..width).map(|x| brightest_row(&frame, width, height, x)).collect();
+        assert!(rows.iter().any(|&row| row < height / 2 - 4) && rows.iter().any(|&row| row > height / 2 + 4));
+        for x in 0..width {
+            assert!(frame[(rows[x] * width + x) * 4 + 1] > 150, "column {} has no bright trace", x);
+        }
+        assert!(frame[..3].iter().all(|&channel| channel < 80));
+    }
+
+    #[test]
+    fn picked_background_displays_as_picked() {
+        // Zero exposure tone maps the whole scene to black, leaving only the background
+        let dark = WaveParams { exposure: 0.0, ..WaveParams::default() };
+        // Persistence adds the history fade toward the background
+        let fading = WaveParams { persistence: 0.5, ..dark.clone() };
+        for format in [wgpu::TextureFormat::Rgba8Unorm, wgpu::TextureFormat::Rgba8UnormSrgb] {
+            for params in [&dark, &fading] {
+                let Some(mut renderer) = headless_renderer_with_format(64, 32, format) else {
+                    return;
+                };
+                renderer.set_background(0.5, 0.25, 0.75);
+                renderer.render(0.0, params, &[0.0; NUM_BANDS]).unwrap();
+                renderer.render(0.1, params, &[0.0; NUM_BANDS]).unwrap();
+                let frame = block_on(renderer.read_frame()).unwrap();
+
+                // Every pixel shows the picked color in 8-bit sRGB
+                for pixel in frame.chunks_exact(4) {
+                    for (channel, expected) in pixel.iter().zip([128, 64, 191]) {
+                        assert!(channel.abs_diff(expected) <= 1, "{:?}: {:?}", format, pixel);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn constrained_gpu_rejects_demanding_modes() {
+        use renderer::{mode_requirements, ModeCapabilities};
+
+        let webgl2 = ModeCapabilities {
+            downlevel_flags: wgpu::DownlevelFlags::empty(),
+            max_texture_dimension_2d: 2048,
+            max_vertex_attributes: 16,
+            depth_attachment: true,
+        };
+        assert!(WaveMode::ALL.iter().all(|&mode| webgl2.covers(&mode_requirements(mode))));
+
+        let no_depth = ModeCapabilities { depth_attachment: false, ..webgl2 };
+        assert!(!no_depth.covers(&mode_requirements(WaveMode::Bars3D)));
+        assert!(no_depth.covers(&mode_requirements(WaveMode::Particles)));
+
+        let tiny_textures = ModeCapabilities { max_texture_dimension_2d: 64, ..webgl2 };
+        assert!(!tiny_textures.covers(&mode_requirements(WaveMode::Spectrogram)));
+        assert!(tiny_textures.covers(&mode_requirements(WaveMode::SineWaves)));
+
+        let few_attributes = ModeCapabilities { max_vertex_attributes: 2, ..webgl2 };
+        assert!(!few_attributes.covers(&mode_requirements(WaveMode::Particles)));
+        assert!(few_attributes.covers(&mode_requirements(WaveMode::Tunnel)));
+    }
+
+    #[test]
+    fn history_size_stays_within_texture_limit() {
+        use renderer::scaled_history_size;
+
+        assert_eq!(scaled_history_size((1600, 900), 1.0, 1, 2048), (1600, 900));
+        // Supersampling clamps each side to the limit on its own
+        assert_eq!(scaled_history_size((1600, 900), 2.0, 1, 2048), (2048, 1800));
+        assert_eq!(scaled_history_size((2048, 2048), 2.0, 1, 2048), (2048, 2048));
+        assert_eq!(scaled_history_size((2048, 2048), 2.0, 4, 2048), (1024, 1024));
+        assert_eq!(scaled_history_size((3, 3), 0.25, 4, 2048), (1, 1));
+    }
 }
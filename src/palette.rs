@@ -0,0 +1,187 @@
+//! Named color palettes
+
+/// Named color palette presets
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Palette {
+    Cyberpunk,
+    Sunset,
+    Mono,
+    Matrix,
+    Ocean,
+    Fire,
+}
+
+/// Colors applied by a palette
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PaletteColors {
+    /// Primary hue (0-360)
+    pub hue: f32,
+    /// Secondary glow hue, relative to the primary (degrees)
+    pub hue2: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 6] = [
+        Palette::Cyberpunk,
+        Palette::Sunset,
+        Palette::Mono,
+        Palette::Matrix,
+        Palette::Ocean,
+        Palette::Fire,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Palette::Cyberpunk => "cyberpunk",
+            Palette::Sunset => "sunset",
+            Palette::Mono => "mono",
+            Palette::Matrix => "matrix",
+            Palette::Ocean => "ocean",
+            Palette::Fire => "fire",
+        }
+    }
+
+    /// Look up a palette by name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Palette> {
+        Palette::ALL
+            .iter()
+            .copied()
+            .find(|palette| palette.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    pub fn colors(self) -> PaletteColors {
+        let (hue, hue2, saturation, lightness) = match self {
+            Palette::Cyberpunk => (180.0, 180.0, 0.9, 0.5),
+            Palette::Sunset => (20.0, 300.0, 0.95, 0.5),
+            Palette::Mono => (0.0, 0.0, 0.0, 0.55),
+            Palette::Matrix => (120.0, 20.0, 0.9, 0.45),
+            Palette::Ocean => (200.0, 40.0, 0.8, 0.5),
+            Palette::Fire => (10.0, 30.0, 1.0, 0.5),
+        };
+
+        PaletteColors {
+            hue,
+            hue2,
+            saturation,
+            lightness,
+        }
+    }
+}
+
+/// Wrap any hue (including negative) into 0.0..360.0
+pub fn wrap_hue(hue: f32) -> f32 {
+    let wrapped = hue.rem_euclid(360.0);
+    // Tiny negative inputs round up to exactly 360.0 in f32
+    if wrapped >= 360.0 {
+        0.0
+    } else {
+        wrapped
+    }
+}
+
+/// Convert an sRGB-encoded channel (0.0 - 1.0, as from a color picker) to linear light
+pub fn srgb_to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Convert HSL (hue in degrees, saturation and lightness 0.0 - 1.0) to RGB, matching the shader's `hsl2rgb`
+///
+/// Chroma `c = (1 - |2l - 1|) * s`, `x = c * (1 - |(h / 60) mod 2 - 1|)`; the 60° sector
+/// picks the (c, x, 0) permutation, then `l - c / 2` is added to every channel.
+/// The shader must keep this exact formula so CPU thumbnails match the live render.
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> [f32; 3] {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let hp = h / 60.0;
+    let x = c * (1.0 - (hp % 2.0 - 1.0).abs());
+
+    let (r, g, b) = if hp < 1.0 {
+        (c, x, 0.0)
+    } else if hp < 2.0 {
+        (x, c, 0.0)
+    } else if hp < 3.0 {
+        (0.0, c, x)
+    } else if hp < 4.0 {
+        (0.0, x, c)
+    } else if hp < 5.0 {
+        (x, 0.0, c)
+    } else {
+        (c, 0.0, x)
+    };
+
+    let m = l - c / 2.0;
+    [r + m, g + m, b + m]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrap_hue_maps_into_range() {
+        assert_eq!(wrap_hue(0.0), 0.0);
+        assert_eq!(wrap_hue(180.0), 180.0);
+        assert_eq!(wrap_hue(360.0), 0.0);
+        assert_eq!(wrap_hue(725.0), 5.0);
+        assert_eq!(wrap_hue(-30.0), 330.0);
+        assert_eq!(wrap_hue(-360.0), 0.0);
+        assert_eq!(wrap_hue(-750.0), 330.0);
+    }
+
+    fn assert_rgb(hue: f32, saturation: f32, lightness: f32, expected: [f32; 3]) {
+        let actual = hsl_to_rgb(hue, saturation, lightness);
+        for (channel, (a, e)) in actual.iter().zip(expected).enumerate() {
+            assert!(
+                (a - e).abs() < 1e-5,
+                "hsl({}, {}, {}) channel {}: expected {:?}, got {:?}",
+                hue, saturation, lightness, channel, expected, actual
+            );
+        }
+    }
+
+    #[test]
+    fn hsl_to_rgb_primaries() {
+        assert_rgb(0.0, 1.0, 0.5, [1.0, 0.0, 0.0]);
+        assert_rgb(120.0, 1.0, 0.5, [0.0, 1.0, 0.0]);
+        assert_rgb(240.0, 1.0, 0.5, [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn hsl_to_rgb_secondaries_and_midpoints() {
+        assert_rgb(60.0, 1.0, 0.5, [1.0, 1.0, 0.0]);
+        assert_rgb(180.0, 1.0, 0.5, [0.0, 1.0, 1.0]);
+        assert_rgb(300.0, 1.0, 0.5, [1.0, 0.0, 1.0]);
+        assert_rgb(30.0, 1.0, 0.5, [1.0, 0.5, 0.0]);
+        assert_rgb(210.0, 0.5, 0.25, [0.125, 0.25, 0.375]);
+    }
+
+    #[test]
+    fn hsl_to_rgb_achromatic() {
+        assert_rgb(200.0, 0.0, 0.5, [0.5, 0.5, 0.5]);
+        assert_rgb(90.0, 1.0, 0.0, [0.0, 0.0, 0.0]);
+        assert_rgb(90.0, 1.0, 1.0, [1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn srgb_to_linear_known_values() {
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-6);
+        // sRGB mid-grey 0.5 (128/255) is ~21.4% linear light
+        assert!((srgb_to_linear(0.5) - 0.214_041).abs() < 1e-5);
+        // The linear toe below the 0.04045 threshold
+        assert!((srgb_to_linear(0.02) - 0.02 / 12.92).abs() < 1e-7);
+    }
+
+    #[test]
+    fn wrap_hue_never_returns_360() {
+        for &hue in &[-1e-6, -1e-9, 359.99999, 720.0 - 1e-5] {
+            let wrapped = wrap_hue(hue);
+            assert!((0.0..360.0).contains(&wrapped), "{} wrapped to {}", hue, wrapped);
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Declarative bindings from audio features to wave parameters
+
+use crate::palette::wrap_hue;
+use crate::wave::WaveParams;
+
+/// Audio feature driving a binding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactiveSource {
+    Bass,
+    Mid,
+    Treble,
+    Rms,
+    Centroid,
+}
+
+impl ReactiveSource {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(ReactiveSource::Bass),
+            1 => Some(ReactiveSource::Mid),
+            2 => Some(ReactiveSource::Treble),
+            3 => Some(ReactiveSource::Rms),
+            4 => Some(ReactiveSource::Centroid),
+            _ => None,
+        }
+    }
+}
+
+/// Wave parameter a binding drives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactiveTarget {
+    Amplitude,
+    Frequency,
+    Speed,
+    Hue,
+}
+
+impl ReactiveTarget {
+    pub fn from_u32(value: u32) -> Option<Self> {
+        match value {
+            0 => Some(ReactiveTarget::Amplitude),
+            1 => Some(ReactiveTarget::Frequency),
+            2 => Some(ReactiveTarget::Speed),
+            3 => Some(ReactiveTarget::Hue),
+            _ => None,
+        }
+    }
+}
+
+/// Per-frame audio features, each normalized to 0.0 - 1.0
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReactiveInputs {
+    pub bass: f32,
+    pub mid: f32,
+    pub treble: f32,
+    pub rms: f32,
+    pub centroid: f32,
+}
+
+impl ReactiveInputs {
+    /// Split `bands` into bass (lowest quarter), mid and treble (highest quarter) averages
+    pub fn new(bands: &[f32], rms: f32, centroid: f32) -> Self {
+        let average = |range: &[f32]| {
+            if range.is_empty() {
+                0.0
+            } else {
+                range.iter().sum::<f32>() / range.len() as f32
+            }
+        };
+        let low = (bands.len() / 4).max(1).min(bands.len());
+        let high = bands.len().saturating_sub(bands.len() / 4).max(low);
+
+        ReactiveInputs {
+            bass: average(&bands[..low]),
+            mid: average(&bands[low..high]),
+            treble: average(&bands[high..]),
+            rms,
+            centroid,
+        }
+    }
+
+    pub fn level(&self, source: ReactiveSource) -> f32 {
+        match source {
+            ReactiveSource::Bass => self.bass,
+            ReactiveSource::Mid => self.mid,
+            ReactiveSource::Treble => self.treble,
+            ReactiveSource::Rms => self.rms,
+            ReactiveSource::Centroid => self.centroid,
+        }
+    }
+}
+
+/// Adds `source * scale` to `target` every frame
+#[derive(Debug, Clone, Copy)]
+pub struct ReactiveBinding {
+    pub source: ReactiveSource,
+    pub target: ReactiveTarget,
+    pub scale: f32,
+}
+
+impl ReactiveBinding {
+    pub fn apply(&self, inputs: &ReactiveInputs, params: &mut WaveParams) {
+        let delta = inputs.level(self.source) * self.scale;
+        match self.target {
+            ReactiveTarget::Amplitude => params.amplitude = (params.amplitude + delta).max(0.0),
+            ReactiveTarget::Frequency => params.frequency = (params.frequency + delta).max(0.1),
+            ReactiveTarget::Speed => params.speed = (params.speed + delta).max(0.0),
+            ReactiveTarget::Hue => params.hue = wrap_hue(params.hue + delta),
+        }
+    }
+}
@@ -1,11 +1,149 @@
 //! WGPU Renderer for wave visualization
 
+use std::sync::{Arc, Mutex};
+
 use wasm_bindgen::prelude::*;
 use wgpu::util::DeviceExt;
-use web_sys::HtmlCanvasElement;
+#[cfg(target_arch = "wasm32")]
+use web_sys::{HtmlCanvasElement, OffscreenCanvas};
 use bytemuck::{Pod, Zeroable};
 
-use crate::wave::WaveParams;
+use crate::error::OscError;
+use crate::palette::srgb_to_linear;
+use crate::wave::{WaveMode, WaveParams, DEFAULT_LAYERS};
+
+/// Number of frequency bands uploaded to the shaders
+pub const NUM_BANDS: usize = 8;
+
+/// Columns of spectrum history kept by `WaveMode::Spectrogram`
+pub const SPECTROGRAM_COLUMNS: u32 = 256;
+
+/// Frequency rows per spectrogram column
+pub const SPECTROGRAM_BANDS: usize = 128;
+
+/// Default number of particles for `WaveMode::Particles`
+pub const DEFAULT_PARTICLE_COUNT: u32 = 2048;
+
+/// Default columns and rows of `WaveMode::Bars3D`
+pub const DEFAULT_BAR_GRID: (u32, u32) = (16, 12);
+
+/// Largest `WaveMode::Bars3D` grid per side
+const MAX_BAR_GRID: u32 = 64;
+
+/// Most (side, mid) points `WaveMode::Goniometer` traces per frame
+pub const GONIOMETER_POINTS: usize = 1024;
+
+/// Depth buffer format for passes drawn with depth testing
+const DEPTH_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth32Float;
+
+/// Renderer settings fixed at creation; the defaults match the plain `Renderer::new`
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy)]
+pub struct RendererConfig {
+    /// GPU preference (0 none, 1 low power, 2 high performance)
+    pub power_preference: u32,
+    /// Presentation (0 auto vsync, 1 auto no vsync, 2 fifo, 3 mailbox, 4 immediate)
+    pub present_mode: u32,
+    /// Multisample anti-aliasing sample count (1 or 4)
+    pub msaa_samples: u32,
+    /// Pick an sRGB surface format when available
+    pub prefer_srgb: bool,
+    /// Background color (sRGB RGBA, as from a color picker); None keeps the built-in dark blue
+    #[wasm_bindgen(skip)]
+    pub clear_color: Option<[f32; 4]>,
+    /// Composite over the page: skip the background and output premultiplied alpha
+    pub transparent: bool,
+}
+
+impl Default for RendererConfig {
+    fn default() -> Self {
+        RendererConfig {
+            power_preference: 2,
+            present_mode: 0,
+            msaa_samples: 1,
+            prefer_srgb: true,
+            clear_color: None,
+            transparent: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl RendererConfig {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the background color (sRGB RGBA, 0.0 - 1.0)
+    pub fn set_clear_color(&mut self, r: f32, g: f32, b: f32, a: f32) {
+        self.clear_color = Some([r, g, b, a].map(|channel| channel.clamp(0.0, 1.0)));
+    }
+}
+
+impl RendererConfig {
+    /// Reject options no backend supports
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    fn validate(&self) -> Result<(), OscError> {
+        if !matches!(self.msaa_samples, 1 | 4) {
+            return Err(OscError::InvalidParam(format!(
+                "Invalid MSAA sample count {}: must be 1 or 4",
+                self.msaa_samples
+            )));
+        }
+        Ok(())
+    }
+
+    fn wgpu_power_preference(&self) -> wgpu::PowerPreference {
+        match self.power_preference {
+            0 => wgpu::PowerPreference::None,
+            1 => wgpu::PowerPreference::LowPower,
+            _ => wgpu::PowerPreference::HighPerformance,
+        }
+    }
+
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    fn wgpu_present_mode(&self) -> wgpu::PresentMode {
+        match self.present_mode {
+            1 => wgpu::PresentMode::AutoNoVsync,
+            2 => wgpu::PresentMode::Fifo,
+            3 => wgpu::PresentMode::Mailbox,
+            4 => wgpu::PresentMode::Immediate,
+            _ => wgpu::PresentMode::AutoVsync,
+        }
+    }
+}
+
+/// Built-in background, cleared to as-is and tone mapped with the scene (w = 0: not picked)
+const DEFAULT_BACKGROUND: [f32; 4] = [0.02, 0.02, 0.05, 0.0];
+
+/// Convert an sRGB background color to the clear value for a target of `format`
+///
+/// Clear values are written in the texture's color space: sRGB formats expect linear
+/// values and encode them on store, so only those need converting.
+fn clear_color_for(color: [f32; 4], format: wgpu::TextureFormat) -> wgpu::Color {
+    let [r, g, b, a] = color;
+    let [r, g, b] = if format.is_srgb() { [r, g, b].map(srgb_to_linear) } else { [r, g, b] };
+    let [r, g, b, a] = [r, g, b, a].map(f64::from);
+    wgpu::Color { r, g, b, a }
+}
+
+/// Clear value and shader background for an sRGB `color`, or the built-in default
+///
+/// A picked color reaches the shaders as its clear value (w = 1), so they composite it
+/// after tone mapping and it displays as picked. The built-in one keeps its original look.
+fn background_for(color: Option<[f32; 4]>, format: wgpu::TextureFormat) -> (wgpu::Color, [f32; 4]) {
+    match color {
+        Some(color) => {
+            let clear = clear_color_for(color, format);
+            (clear, [clear.r as f32, clear.g as f32, clear.b as f32, 1.0])
+        }
+        None => {
+            let [r, g, b, _] = DEFAULT_BACKGROUND.map(f64::from);
+            (wgpu::Color { r, g, b, a: 1.0 }, DEFAULT_BACKGROUND)
+        }
+    }
+}
 
 /// Vertex data for wave mesh
 #[repr(C)]
@@ -30,6 +168,105 @@ impl Vertex {
     }
 }
 
+/// Per-instance particle data for the particle field
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct Particle {
+    pub position: [f32; 2],
+    pub band: f32,
+    pub seed: f32,
+}
+
+impl Particle {
+    const ATTRIBS: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
+        0 => Float32x2,
+        1 => Float32,
+        2 => Float32
+    ];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Particle>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+
+    /// Scatter `count` particles pseudo-randomly, assigning bands left (bass) to right (treble)
+    pub fn scatter(count: u32) -> Vec<Particle> {
+        (0..count)
+            .map(|i| {
+                let x = hash(i * 2) * 2.0 - 1.0;
+                let y = hash(i * 2 + 1) * 2.0 - 1.0;
+                let band = (((x + 1.0) * 0.5 * NUM_BANDS as f32) as usize).min(NUM_BANDS - 1);
+                Particle {
+                    position: [x, y],
+                    band: band as f32,
+                    seed: hash(i ^ 0x5bd1_e995),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Per-instance bar data for `WaveMode::Bars3D`
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct Bar {
+    /// Floor center (x, z) and footprint (width, depth), all in -1.0 - 1.0
+    pub cell: [f32; 4],
+    pub height: f32,
+    /// Column position, 0.0 (bass) - 1.0 (treble)
+    pub column: f32,
+}
+
+impl Bar {
+    const ATTRIBS: [wgpu::VertexAttribute; 3] = wgpu::vertex_attr_array![
+        0 => Float32x4,
+        1 => Float32,
+        2 => Float32
+    ];
+
+    pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+        wgpu::VertexBufferLayout {
+            array_stride: std::mem::size_of::<Bar>() as wgpu::BufferAddress,
+            step_mode: wgpu::VertexStepMode::Instance,
+            attributes: &Self::ATTRIBS,
+        }
+    }
+
+    /// Lay out a `cols x rows` floor grid with `heights` (row-major, front row first)
+    pub fn grid(cols: u32, rows: u32, heights: &[f32]) -> Vec<Bar> {
+        let (width, depth) = (2.0 / cols as f32, 2.0 / rows as f32);
+        heights
+            .iter()
+            .enumerate()
+            .map(|(i, &height)| {
+                let (col, row) = (i as u32 % cols, i as u32 / cols);
+                Bar {
+                    cell: [
+                        -1.0 + (col as f32 + 0.5) * width,
+                        -1.0 + (row as f32 + 0.5) * depth,
+                        width * 0.8,
+                        depth * 0.8,
+                    ],
+                    height,
+                    column: if cols > 1 { col as f32 / (cols - 1) as f32 } else { 0.0 },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Integer hash mapped to 0.0 - 1.0, used for deterministic particle placement
+fn hash(value: u32) -> f32 {
+    let mut x = value.wrapping_mul(0x9e37_79b9);
+    x ^= x >> 16;
+    x = x.wrapping_mul(0x85eb_ca6b);
+    x ^= x >> 13;
+    x as f32 / u32::MAX as f32
+}
+
 /// Uniform data passed to shaders
 #[repr(C)]
 #[derive(Copy, Clone, Debug, Pod, Zeroable)]
@@ -41,8 +278,46 @@ pub struct Uniforms {
     pub resolution: [f32; 2],
     pub hue: f32,
     pub mode: u32,
+    pub target_mode: u32,
+    pub mix: f32,
+    pub symmetry: u32,
+    pub polar: u32,
+    pub bands: [[f32; 4]; NUM_BANDS / 4],
+    pub line_width: f32,
+    pub exposure: f32,
+    pub gamma: f32,
+    pub grid: u32,
+    pub hue2: f32,
+    pub saturation: f32,
+    pub lightness: f32,
+    pub _align: f32,
+    pub camera_offset: [f32; 2],
+    pub persistence: f32,
+    pub color_by_frequency: u32,
+    pub layers: u32,
+    pub aberration: f32,
+    pub mouse: [f32; 2],
+    pub intensity_from_amplitude: f32,
+    /// Spectrogram write cursor as a fraction of the history width
+    pub spectrogram_cursor: f32,
+    pub phase: f32,
+    pub transparent: u32,
+    /// Tempo pulse phase through the current beat (0.0 - 1.0)
+    pub pulse_phase: f32,
+    pub pulse_enabled: u32,
+    /// Bars3D column to highlight while dimming the rest (-1 for none)
+    pub highlight_band: i32,
+    /// Columns of the Bars3D grid, mapping bar positions back to band indices
+    pub bar_columns: u32,
+    /// Background behind the scene and faded toward by persistence; w = 1 for a picked
+    /// color in the output's color space, 0 for the built-in one tone mapped with the scene
+    pub background: [f32; 4],
 }
 
+// WGSL rounds the uniform struct up to a multiple of 16 bytes; a smaller buffer fails
+// binding validation, so any trailing gap must be filled with explicit padding fields
+const _: () = assert!(std::mem::size_of::<Uniforms>().is_multiple_of(16));
+
 impl Default for Uniforms {
     fn default() -> Self {
         Uniforms {
@@ -53,279 +328,2044 @@ impl Default for Uniforms {
             resolution: [800.0, 600.0],
             hue: 180.0,
             mode: 0,
+            target_mode: 0,
+            mix: 0.0,
+            symmetry: 0,
+            polar: 0,
+            bands: [[0.0; 4]; NUM_BANDS / 4],
+            line_width: 0.08,
+            exposure: 1.0,
+            gamma: 2.2,
+            grid: 0,
+            hue2: 180.0,
+            saturation: 0.9,
+            lightness: 0.5,
+            _align: 0.0,
+            camera_offset: [0.0, 0.0],
+            persistence: 0.0,
+            color_by_frequency: 0,
+            layers: DEFAULT_LAYERS,
+            aberration: 0.0,
+            mouse: [0.5, 0.5],
+            intensity_from_amplitude: 0.0,
+            spectrogram_cursor: 0.0,
+            phase: 0.0,
+            transparent: 0,
+            pulse_phase: 0.0,
+            pulse_enabled: 0,
+            highlight_band: -1,
+            bar_columns: DEFAULT_BAR_GRID.0,
+            background: DEFAULT_BACKGROUND,
+        }
+    }
+}
+
+/// A stage of a frame, encoded in order into the frame's command encoder
+///
+/// Effects that need extra passes (post-processing, compositing) add a
+/// variant here and append it in `Renderer::frame_passes`.
+///
+/// Variants are declared in encoding order: multisampled passes come before the
+/// single-sampled ones, so a later MSAA resolve can't overwrite them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum FramePass {
+    /// Fade the previous frame's history toward the background
+    Fade,
+    /// Fullscreen wave scene
+    Scene,
+    /// Instanced particle field
+    Particles,
+    /// Instanced bar grid, depth tested
+    Bars,
+    /// Stereo sample pairs traced as a line strip
+    Goniometer,
+    /// Copy the history texture to the output
+    Blit,
+}
+
+impl FramePass {
+    fn label(self) -> &'static str {
+        match self {
+            FramePass::Fade => "History Fade Pass",
+            FramePass::Scene => "Wave Render Pass",
+            FramePass::Particles => "Particle Render Pass",
+            FramePass::Bars => "Bars Render Pass",
+            FramePass::Goniometer => "Goniometer Render Pass",
+            FramePass::Blit => "History Blit Pass",
+        }
+    }
+}
+
+/// Adapter and device capabilities the wave modes depend on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ModeCapabilities {
+    /// Downlevel capabilities reported by the adapter
+    pub downlevel_flags: wgpu::DownlevelFlags,
+    /// Largest width or height of a 2D texture
+    pub max_texture_dimension_2d: u32,
+    /// Vertex attributes available to a pipeline, including instanced ones
+    pub max_vertex_attributes: u32,
+    /// Whether `DEPTH_FORMAT` can be rendered to
+    pub depth_attachment: bool,
+}
+
+impl ModeCapabilities {
+    /// Read the capabilities of `adapter` and the `device` created from it
+    fn query(adapter: &wgpu::Adapter, device: &wgpu::Device) -> Self {
+        let limits = device.limits();
+        ModeCapabilities {
+            downlevel_flags: adapter.get_downlevel_capabilities().flags,
+            max_texture_dimension_2d: limits.max_texture_dimension_2d,
+            max_vertex_attributes: limits.max_vertex_attributes,
+            depth_attachment: adapter
+                .get_texture_format_features(DEPTH_FORMAT)
+                .allowed_usages
+                .contains(wgpu::TextureUsages::RENDER_ATTACHMENT),
+        }
+    }
+
+    /// Whether these capabilities meet everything in `required`
+    pub fn covers(&self, required: &ModeCapabilities) -> bool {
+        self.downlevel_flags.contains(required.downlevel_flags)
+            && self.max_texture_dimension_2d >= required.max_texture_dimension_2d
+            && self.max_vertex_attributes >= required.max_vertex_attributes
+            && (self.depth_attachment || !required.depth_attachment)
+    }
+}
+
+/// History texture size: `size` scaled by `render_scale`, then divided by `pixelate`
+///
+/// Each side is clamped to `max_dimension`, as supersampling a large canvas can exceed it.
+pub fn scaled_history_size(size: (u32, u32), render_scale: f32, pixelate: u32, max_dimension: u32) -> (u32, u32) {
+    let scaled = |length: u32| ((length as f32 * render_scale).round() as u32 / pixelate).clamp(1, max_dimension);
+    (scaled(size.0), scaled(size.1))
+}
+
+/// Capabilities `mode` needs; the mesh modes only need the WebGL2 baseline
+///
+/// Modes relying on storage buffers or compute add e.g. `VERTEX_STORAGE` here.
+pub fn mode_requirements(mode: WaveMode) -> ModeCapabilities {
+    let baseline = ModeCapabilities {
+        downlevel_flags: wgpu::DownlevelFlags::empty(),
+        max_texture_dimension_2d: 1,
+        max_vertex_attributes: Vertex::ATTRIBS.len() as u32,
+        depth_attachment: false,
+    };
+    match mode {
+        WaveMode::SineWaves
+        | WaveMode::CircularRipples
+        | WaveMode::LissajousCurves
+        | WaveMode::PlasmaField
+        | WaveMode::WaveSurface
+        | WaveMode::Tunnel
+        | WaveMode::Goniometer => baseline,
+        // One instance per particle, attributes stepped per instance
+        WaveMode::Particles => ModeCapabilities {
+            max_vertex_attributes: Particle::ATTRIBS.len() as u32,
+            ..baseline
+        },
+        // The scrolling spectrum history is a single texture
+        WaveMode::Spectrogram => ModeCapabilities {
+            max_texture_dimension_2d: SPECTROGRAM_COLUMNS.max(SPECTROGRAM_BANDS as u32),
+            ..baseline
+        },
+        // Instanced boxes, depth tested against each other
+        WaveMode::Bars3D => ModeCapabilities {
+            max_vertex_attributes: Bar::ATTRIBS.len() as u32,
+            depth_attachment: true,
+            ..baseline
+        },
+    }
+}
+
+/// Ping-pong offscreen textures the scene is drawn into before reaching the output
+///
+/// Used for phosphor persistence, pixelation (rendering at `1 / pixelate` size) and the
+/// render scale.
+struct HistoryTargets {
+    /// Pixelation divisor the textures were sized for
+    pixelate: u32,
+    /// Texture size in pixels
+    size: (u32, u32),
+    /// Whether the scene is max-blended over the faded history
+    persistent: bool,
+    textures: [wgpu::Texture; 2],
+    views: [wgpu::TextureView; 2],
+    /// Bind groups sampling each texture
+    bind_groups: [wgpu::BindGroup; 2],
+    /// Index of the texture being drawn this frame
+    current: usize,
+}
+
+/// Render pipelines, built against the output format
+///
+/// Layouts and the shader live on `Renderer`, so reconfiguring (e.g. a new
+/// output format) only needs `Pipelines::new` again.
+struct Pipelines {
+    /// Fullscreen wave scene
+    scene: wgpu::RenderPipeline,
+    /// Instanced particle field
+    particles: wgpu::RenderPipeline,
+    /// Instanced, depth-tested bar grid
+    bars: wgpu::RenderPipeline,
+    /// Line strip through the goniometer points
+    goniometer: wgpu::RenderPipeline,
+    /// Wave scene max-blended over the faded history
+    persistent_scene: wgpu::RenderPipeline,
+    fade: wgpu::RenderPipeline,
+    blit: wgpu::RenderPipeline,
+    /// Multisampled scene and particles for drawing straight to the output with MSAA
+    msaa_scene: Option<wgpu::RenderPipeline>,
+    msaa_particles: Option<wgpu::RenderPipeline>,
+}
+
+/// Runtime-adjustable settings the pipelines are built with
+#[derive(Debug, Clone, Copy)]
+struct PipelineOptions {
+    /// Polygon mode of the wave scene (`Line` for wireframe)
+    scene_polygon_mode: wgpu::PolygonMode,
+    /// Blend of the scene and particles over the target
+    blend: Option<wgpu::BlendState>,
+    /// Sample count of the multisampled variants (1 skips them)
+    msaa_samples: u32,
+}
+
+/// Rasterization settings of a pipeline
+#[derive(Debug, Clone, Copy)]
+struct Raster {
+    polygon_mode: wgpu::PolygonMode,
+    samples: u32,
+}
+
+impl Raster {
+    const FILL: Raster = Raster {
+        polygon_mode: wgpu::PolygonMode::Fill,
+        samples: 1,
+    };
+}
+
+impl Pipelines {
+    fn new(
+        device: &wgpu::Device,
+        shader: &wgpu::ShaderModule,
+        layout: &wgpu::PipelineLayout,
+        history_layout: &wgpu::PipelineLayout,
+        format: wgpu::TextureFormat,
+        options: PipelineOptions,
+    ) -> Self {
+        let PipelineOptions {
+            scene_polygon_mode,
+            blend,
+            msaa_samples,
+        } = options;
+        let target = |blend| wgpu::ColorTargetState {
+            format,
+            blend,
+            write_mask: wgpu::ColorWrites::ALL,
+        };
+        let scene_raster = Raster {
+            polygon_mode: scene_polygon_mode,
+            samples: 1,
+        };
+        let scene = Self::create_fullscreen_pipeline(
+            device,
+            "Wave Render Pipeline",
+            layout,
+            shader,
+            "fs_main",
+            target(blend),
+            scene_raster,
+        );
+        let particles = Self::create_particle_pipeline(
+            device,
+            layout,
+            shader,
+            target(blend),
+            Raster::FILL,
+        );
+
+        // Opaque unless fading out during a mode cross-fade
+        let bars = Self::create_bar_pipeline(device, layout, shader, target(Some(wgpu::BlendState::ALPHA_BLENDING)));
+        let goniometer = Self::create_goniometer_pipeline(device, layout, shader, target(blend));
+
+        let (msaa_scene, msaa_particles) = if msaa_samples > 1 {
+            let scene = Self::create_fullscreen_pipeline(
+                device,
+                "Multisampled Wave Render Pipeline",
+                layout,
+                shader,
+                "fs_main",
+                target(blend),
+                Raster {
+                    samples: msaa_samples,
+                    ..scene_raster
+                },
+            );
+            let particles = Self::create_particle_pipeline(
+                device,
+                layout,
+                shader,
+                target(blend),
+                Raster {
+                    samples: msaa_samples,
+                    ..Raster::FILL
+                },
+            );
+            (Some(scene), Some(particles))
+        } else {
+            (None, None)
+        };
+
+        let max_blend = wgpu::BlendComponent {
+            src_factor: wgpu::BlendFactor::One,
+            dst_factor: wgpu::BlendFactor::One,
+            operation: wgpu::BlendOperation::Max,
+        };
+        let persistent_scene = Self::create_fullscreen_pipeline(
+            device,
+            "Persistent Wave Render Pipeline",
+            layout,
+            shader,
+            "fs_main",
+            target(Some(wgpu::BlendState { color: max_blend, alpha: max_blend })),
+            scene_raster,
+        );
+        let fade = Self::create_fullscreen_pipeline(
+            device,
+            "Persistence Fade Pipeline",
+            history_layout,
+            shader,
+            "fs_fade",
+            target(None),
+            Raster::FILL,
+        );
+        let blit = Self::create_fullscreen_pipeline(
+            device,
+            "Persistence Blit Pipeline",
+            history_layout,
+            shader,
+            "fs_blit",
+            target(None),
+            Raster::FILL,
+        );
+
+        Self {
+            scene,
+            particles,
+            bars,
+            goniometer,
+            persistent_scene,
+            fade,
+            blit,
+            msaa_scene,
+            msaa_particles,
         }
     }
+
+    /// Create the instanced particle pipeline, one quad per particle
+    fn create_particle_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        target: wgpu::ColorTargetState,
+        raster: Raster,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Particle Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: "vs_particles",
+                buffers: &[Particle::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: "fs_particles",
+                targets: &[Some(target)],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: raster.polygon_mode,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: raster.samples,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        })
+    }
+
+    /// Create the instanced bar pipeline, one depth-tested cube per bar
+    fn create_bar_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        target: wgpu::ColorTargetState,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Bars Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: "vs_bars",
+                buffers: &[Bar::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: "fs_bars",
+                targets: &[Some(target)],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: Some(wgpu::DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: wgpu::CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        })
+    }
+
+    /// Create the goniometer pipeline: one (side, mid) vertex per point, joined as a line strip
+    fn create_goniometer_pipeline(
+        device: &wgpu::Device,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        target: wgpu::ColorTargetState,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Goniometer Render Pipeline"),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: "vs_goniometer",
+                buffers: &[wgpu::VertexBufferLayout {
+                    array_stride: std::mem::size_of::<[f32; 2]>() as wgpu::BufferAddress,
+                    step_mode: wgpu::VertexStepMode::Vertex,
+                    attributes: &wgpu::vertex_attr_array![0 => Float32x2],
+                }],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: "fs_goniometer",
+                targets: &[Some(target)],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::LineStrip,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: wgpu::PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        })
+    }
+
+    /// Create a pipeline drawing the fullscreen quad with the given fragment entry point
+    fn create_fullscreen_pipeline(
+        device: &wgpu::Device,
+        label: &str,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        fragment_entry: &str,
+        target: wgpu::ColorTargetState,
+        raster: Raster,
+    ) -> wgpu::RenderPipeline {
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
+            vertex: wgpu::VertexState {
+                module: shader,
+                entry_point: "vs_main",
+                buffers: &[Vertex::desc()],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: shader,
+                entry_point: fragment_entry,
+                targets: &[Some(target)],
+            }),
+            primitive: wgpu::PrimitiveState {
+                topology: wgpu::PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: wgpu::FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: raster.polygon_mode,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState {
+                count: raster.samples,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            multiview: None,
+        })
+    }
+}
+
+/// Where finished frames go
+enum RenderOutput {
+    /// A canvas surface, presented after each frame (wasm32 only)
+    #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+    Surface(wgpu::Surface<'static>),
+    /// An owned texture for headless rendering, read back with `read_frame`
+    Texture(wgpu::Texture),
 }
 
 /// Main WGPU Renderer
 pub struct Renderer {
-    surface: wgpu::Surface<'static>,
+    output: RenderOutput,
     device: wgpu::Device,
     queue: wgpu::Queue,
     config: wgpu::SurfaceConfiguration,
     size: (u32, u32),
-    render_pipeline: wgpu::RenderPipeline,
+    /// Size requested by `resize`, applied at the start of the next frame
+    pending_size: Option<(u32, u32)>,
+    shader: wgpu::ShaderModule,
+    pipeline_layout: wgpu::PipelineLayout,
+    history_pipeline_layout: wgpu::PipelineLayout,
+    pipelines: Pipelines,
     vertex_buffer: wgpu::Buffer,
     index_buffer: wgpu::Buffer,
     num_indices: u32,
+    /// Subdivisions per side of the fullscreen quad
+    grid_size: u32,
+    /// Whether the wave scene is drawn as a wireframe
+    wireframe: bool,
+    /// Blend of the scene and particles over the target (None replaces)
+    blend: Option<wgpu::BlendState>,
     uniform_buffer: wgpu::Buffer,
     uniform_bind_group: wgpu::BindGroup,
+    particle_buffer: wgpu::Buffer,
+    num_particles: u32,
+    /// Columns and rows of the `WaveMode::Bars3D` grid
+    bar_grid: (u32, u32),
+    /// Bar heights, row-major with the newest row first
+    bar_heights: Vec<f32>,
+    bar_buffer: wgpu::Buffer,
+    /// (side, mid) points of `WaveMode::Goniometer`, `GONIOMETER_POINTS` capacity
+    goniometer_buffer: wgpu::Buffer,
+    goniometer_count: u32,
+    /// Depth buffer for the bar pass, sized to the scene target
+    depth_target: Option<(wgpu::Texture, wgpu::TextureView)>,
+    texture_bind_group_layout: wgpu::BindGroupLayout,
+    history_sampler: wgpu::Sampler,
+    /// Linear sampler for smooth render scale upscales
+    scaled_sampler: wgpu::Sampler,
+    history: Option<HistoryTargets>,
+    /// Resolution multiplier of the scene relative to the output (0.25 - 2.0)
+    render_scale: f32,
+    /// Passes of the most recent frame, replayed by `read_pixel` when there is no history
+    last_passes: Vec<FramePass>,
+    /// Clear the history instead of fading it on the next frame
+    clear_history: bool,
+    /// Background the frame is cleared to
+    clear_color: wgpu::Color,
+    /// Background the shaders draw and fade toward
+    background: [f32; 4],
+    /// Skip the background so the canvas composites over the page
+    transparent: bool,
+    /// MSAA sample count for passes drawn straight to the output (1 disables)
+    msaa_samples: u32,
+    /// Multisampled color target resolved into the output, when MSAA is on
+    msaa_target: Option<(wgpu::Texture, wgpu::TextureView)>,
+    /// Output region drawn to as (x, y, width, height) in pixels; None covers the whole output
+    viewport: Option<[f32; 4]>,
+    /// Output region that may be written as (x, y, width, height); when set, the rest is kept
+    scissor: Option<[u32; 4]>,
+    /// Ring buffer of spectrum columns (`SPECTROGRAM_COLUMNS x SPECTROGRAM_BANDS`)
+    spectrogram_texture: wgpu::Texture,
+    /// Column the next spectrum is written to
+    spectrogram_cursor: u32,
+    /// Capabilities checked by `supports_mode`
+    capabilities: ModeCapabilities,
+    /// Uniforms last uploaded, to skip redundant writes while paused
+    last_uniforms: Option<Uniforms>,
 }
 
 impl Renderer {
     /// Create a new renderer for the given canvas
-    pub async fn new(canvas: HtmlCanvasElement) -> Result<Self, JsValue> {
-        let width = canvas.client_width() as u32;
-        let height = canvas.client_height() as u32;
-        
-        // Create WGPU instance
-        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
-            backends: wgpu::Backends::all(),
-            ..Default::default()
-        });
+    #[cfg(target_arch = "wasm32")]
+    pub async fn new(canvas: HtmlCanvasElement, config: RendererConfig) -> Result<Self, OscError> {
+        // A hidden or not yet laid out canvas reports 0; a later resize sets the real size
+        let width = (canvas.client_width() as u32).max(1);
+        let height = (canvas.client_height() as u32).max(1);
+        Self::with_target(wgpu::SurfaceTarget::Canvas(canvas), width, height, config).await
+    }
 
-        // Create surface from canvas
-        let surface = instance.create_surface(wgpu::SurfaceTarget::Canvas(canvas))
-            .map_err(|e| JsValue::from_str(&format!("Failed to create surface: {}", e)))?;
+    /// Create a renderer for an `OffscreenCanvas`, e.g. one transferred to a Web Worker
+    #[cfg(target_arch = "wasm32")]
+    pub async fn new_offscreen(canvas: OffscreenCanvas, config: RendererConfig) -> Result<Self, OscError> {
+        let width = canvas.width().max(1);
+        let height = canvas.height().max(1);
+        Self::with_target(wgpu::SurfaceTarget::OffscreenCanvas(canvas), width, height, config).await
+    }
 
-        // Request adapter
-        let adapter = instance
-            .request_adapter(&wgpu::RequestAdapterOptions {
-                power_preference: wgpu::PowerPreference::HighPerformance,
-                compatible_surface: Some(&surface),
-                force_fallback_adapter: false,
-            })
-            .await
-            .ok_or_else(|| JsValue::from_str("Failed to find suitable GPU adapter"))?;
+    /// Create a renderer drawing to `target` at `width x height`
+    #[cfg(target_arch = "wasm32")]
+    async fn with_target(
+        target: wgpu::SurfaceTarget<'static>,
+        width: u32,
+        height: u32,
+        settings: RendererConfig,
+    ) -> Result<Self, OscError> {
+        settings.validate()?;
+        let instance = Self::create_instance();
 
-        // Request device
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    label: Some("Cyber-Oscilloscope Device"),
-                    required_features: wgpu::Features::empty(),
-                    required_limits: wgpu::Limits::downlevel_webgl2_defaults(),
-                },
-                None,
-            )
-            .await
-            .map_err(|e| JsValue::from_str(&format!("Failed to create device: {}", e)))?;
+        // Creating the surface claims the canvas's context, after which `getContext("2d")`
+        // returns null; acquire the adapter and device first so a failure leaves the canvas
+        // free for the Canvas 2D fallback. WebGPU adapters don't depend on the surface.
+        let adapter = Self::request_adapter(&instance, &settings).await?;
+        let (device, queue) = Self::request_device(&adapter).await?;
+
+        // Create surface from canvas
+        let surface = instance.create_surface(target)
+            .map_err(|e| OscError::SurfaceError(format!("Failed to create surface: {}", e)))?;
 
         // Configure surface
         let surface_caps = surface.get_capabilities(&adapter);
         let surface_format = surface_caps
             .formats
             .iter()
-            .find(|f| f.is_srgb())
+            .find(|f| f.is_srgb() == settings.prefer_srgb)
             .copied()
             .unwrap_or(surface_caps.formats[0]);
 
+        // Auto modes are always available; explicit ones fall back to vsync
+        let mut present_mode = settings.wgpu_present_mode();
+        if !surface_caps.present_modes.contains(&present_mode) {
+            log::warn!("⚠️ Present mode {:?} unsupported, using AutoVsync", present_mode);
+            present_mode = wgpu::PresentMode::AutoVsync;
+        }
+
+        let alpha_mode = if settings.transparent {
+            surface_caps
+                .alpha_modes
+                .iter()
+                .copied()
+                .find(|&mode| mode == wgpu::CompositeAlphaMode::PreMultiplied)
+                .unwrap_or_else(|| {
+                    log::warn!("⚠️ Premultiplied alpha unsupported, canvas stays opaque");
+                    surface_caps.alpha_modes[0]
+                })
+        } else {
+            surface_caps.alpha_modes[0]
+        };
+
         let config = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
             format: surface_format,
             width,
             height,
-            present_mode: wgpu::PresentMode::AutoVsync,
-            alpha_mode: surface_caps.alpha_modes[0],
+            present_mode,
+            alpha_mode,
             view_formats: vec![],
             desired_maximum_frame_latency: 2,
         };
         surface.configure(&device, &config);
 
-        // Create shader module
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: Some("Wave Shader"),
-            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/wave.wgsl").into()),
-        });
+        Self::with_device(&adapter, device, queue, RenderOutput::Surface(surface), config, settings).await
+    }
 
-        // Create uniform buffer
-        let uniforms = Uniforms {
-            resolution: [width as f32, height as f32],
-            ..Default::default()
+    /// Create a renderer drawing into an owned `width x height` texture instead of a canvas
+    ///
+    /// For tests and tools without a browser: `render` draws into the texture and
+    /// `read_frame` reads it back. Native targets use the platform's wgpu backends.
+    pub async fn new_headless(width: u32, height: u32, format: wgpu::TextureFormat) -> Result<Self, OscError> {
+        let settings = RendererConfig::default();
+        let instance = Self::create_instance();
+        let adapter = Self::request_adapter(&instance, &settings).await?;
+        let (device, queue) = Self::request_device(&adapter).await?;
+
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format,
+            width: width.max(1),
+            height: height.max(1),
+            present_mode: wgpu::PresentMode::AutoVsync,
+            alpha_mode: wgpu::CompositeAlphaMode::Opaque,
+            view_formats: vec![],
+            desired_maximum_frame_latency: 2,
         };
-        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Uniform Buffer"),
-            contents: bytemuck::cast_slice(&[uniforms]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+        let texture = Self::create_capture_texture(&device, &config, "Headless Output Texture");
 
-        // Create bind group layout
-        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-            label: Some("Uniform Bind Group Layout"),
-            entries: &[wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Buffer {
-                    ty: wgpu::BufferBindingType::Uniform,
-                    has_dynamic_offset: false,
-                    min_binding_size: None,
-                },
-                count: None,
-            }],
-        });
+        Self::with_device(&adapter, device, queue, RenderOutput::Texture(texture), config, settings).await
+    }
+
+    /// Create a WGPU instance with every backend enabled
+    fn create_instance() -> wgpu::Instance {
+        wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            ..Default::default()
+        })
+    }
+
+    /// Request an adapter matching the configured power preference
+    async fn request_adapter(
+        instance: &wgpu::Instance,
+        settings: &RendererConfig,
+    ) -> Result<wgpu::Adapter, OscError> {
+        instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: settings.wgpu_power_preference(),
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .ok_or(OscError::AdapterUnavailable)
+    }
+
+    /// Request a device, with wireframe support when the adapter offers it
+    async fn request_device(adapter: &wgpu::Adapter) -> Result<(wgpu::Device, wgpu::Queue), OscError> {
+        let optional_features = adapter.features() & wgpu::Features::POLYGON_MODE_LINE;
+        adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("Cyber-Oscilloscope Device"),
+                    required_features: optional_features,
+                    required_limits: wgpu::Limits::downlevel_webgl2_defaults(),
+                },
+                None,
+            )
+            .await
+            .map_err(|e| OscError::DeviceRequestFailed(format!("Failed to create device: {}", e)))
+    }
+
+    /// Build the GPU resources for a configured output
+    async fn with_device(
+        adapter: &wgpu::Adapter,
+        device: wgpu::Device,
+        queue: wgpu::Queue,
+        output: RenderOutput,
+        config: wgpu::SurfaceConfiguration,
+        settings: RendererConfig,
+    ) -> Result<Self, OscError> {
+        let (width, height) = (config.width, config.height);
+        let capabilities = ModeCapabilities::query(adapter, &device);
+        log::info!("🧩 Adapter capabilities: {:?}", capabilities);
+
+        let msaa_samples = if settings.msaa_samples > 1
+            && !adapter
+                .get_texture_format_features(config.format)
+                .flags
+                .sample_count_supported(settings.msaa_samples)
+        {
+            log::warn!("⚠️ {}x MSAA unsupported for {:?}, disabling", settings.msaa_samples, config.format);
+            1
+        } else {
+            settings.msaa_samples
+        };
+
+        // Capture shader and pipeline validation errors instead of letting them abort init
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+        // Create shader module
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Wave Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("../shaders/wave.wgsl").into()),
+        });
+
+        // Create uniform buffer
+        let uniforms = Uniforms {
+            resolution: [width as f32, height as f32],
+            ..Default::default()
+        };
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Uniform Buffer"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Spectrum history for the spectrogram, one column per write
+        let spectrogram_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("Spectrogram Texture"),
+            size: wgpu::Extent3d {
+                width: SPECTROGRAM_COLUMNS,
+                height: SPECTROGRAM_BANDS as u32,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::R8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+            view_formats: &[],
+        });
+        let spectrogram_view = spectrogram_texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // Repeat horizontally so the shader can scroll past the write cursor
+        let spectrogram_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Spectrogram Sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+
+        // Create bind group layout
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Uniform Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
 
         // Create bind group
         let uniform_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             label: Some("Uniform Bind Group"),
             layout: &bind_group_layout,
-            entries: &[wgpu::BindGroupEntry {
-                binding: 0,
-                resource: uniform_buffer.as_entire_binding(),
-            }],
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(&spectrogram_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::Sampler(&spectrogram_sampler),
+                },
+            ],
         });
 
-        // Create pipeline layout
+        // Create pipeline layouts
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
             bind_group_layouts: &[&bind_group_layout],
             push_constant_ranges: &[],
         });
 
-        // Create render pipeline
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: Some("Wave Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main",
-                buffers: &[Vertex::desc()],
-            },
-            fragment: Some(wgpu::FragmentState {
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState {
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList,
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw,
-                cull_mode: None,
-                polygon_mode: wgpu::PolygonMode::Fill,
-                unclipped_depth: false,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: wgpu::MultisampleState {
-                count: 1,
-                mask: !0,
-                alpha_to_coverage_enabled: false,
-            },
-            multiview: None,
+        // Persistence passes additionally sample a history texture
+        let texture_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("History Texture Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
         });
 
-        // Create fullscreen quad vertices
-        let vertices = [
-            Vertex { position: [-1.0, -1.0, 0.0], uv: [0.0, 1.0] },
-            Vertex { position: [1.0, -1.0, 0.0], uv: [1.0, 1.0] },
-            Vertex { position: [1.0, 1.0, 0.0], uv: [1.0, 0.0] },
-            Vertex { position: [-1.0, 1.0, 0.0], uv: [0.0, 0.0] },
-        ];
+        let history_pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("History Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout, &texture_bind_group_layout],
+            push_constant_ranges: &[],
+        });
 
-        let indices: [u16; 6] = [0, 1, 2, 2, 3, 0];
+        let pipelines = Pipelines::new(
+            &device,
+            &shader,
+            &pipeline_layout,
+            &history_pipeline_layout,
+            config.format,
+            PipelineOptions {
+                scene_polygon_mode: wgpu::PolygonMode::Fill,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                msaa_samples,
+            },
+        );
+        if let Some(error) = device.pop_error_scope().await {
+            return Err(OscError::ShaderError(format!("Failed to create render pipelines: {}", error)));
+        }
 
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(&vertices),
-            usage: wgpu::BufferUsages::VERTEX,
+        let particle_buffer = Self::create_particle_buffer(&device, DEFAULT_PARTICLE_COUNT);
+        let bar_buffer = Self::create_bar_buffer(&device, DEFAULT_BAR_GRID.0 * DEFAULT_BAR_GRID.1);
+        let goniometer_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Goniometer Vertex Buffer"),
+            size: std::mem::size_of::<[f32; 2 * GONIOMETER_POINTS]>() as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
         });
 
-        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(&indices),
-            usage: wgpu::BufferUsages::INDEX,
+        // Nearest filtering keeps pixelated upscales chunky
+        let history_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("History Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Nearest,
+            min_filter: wgpu::FilterMode::Nearest,
+            ..Default::default()
+        });
+        let scaled_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("Scaled History Sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
         });
 
+        // Create fullscreen quad
+        let (vertex_buffer, index_buffer, num_indices) = Self::create_grid_buffers(&device, 1);
+
+        let msaa_target = Self::create_msaa_target(&device, &config, msaa_samples);
+        let (clear_color, background) = background_for(settings.clear_color, config.format);
+
         log::info!("🎨 WGPU Renderer created: {}x{}", width, height);
 
         Ok(Self {
-            surface,
+            output,
             device,
             queue,
             config,
             size: (width, height),
-            render_pipeline,
+            pending_size: None,
+            shader,
+            pipeline_layout,
+            history_pipeline_layout,
+            pipelines,
             vertex_buffer,
             index_buffer,
-            num_indices: indices.len() as u32,
+            num_indices,
+            grid_size: 1,
+            wireframe: false,
+            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
             uniform_buffer,
             uniform_bind_group,
+            particle_buffer,
+            num_particles: DEFAULT_PARTICLE_COUNT,
+            bar_grid: DEFAULT_BAR_GRID,
+            bar_heights: vec![0.0; (DEFAULT_BAR_GRID.0 * DEFAULT_BAR_GRID.1) as usize],
+            bar_buffer,
+            goniometer_buffer,
+            goniometer_count: 0,
+            depth_target: None,
+            texture_bind_group_layout,
+            history_sampler,
+            scaled_sampler,
+            history: None,
+            render_scale: 1.0,
+            last_passes: Vec::new(),
+            clear_history: false,
+            clear_color,
+            background,
+            transparent: settings.transparent,
+            msaa_samples,
+            msaa_target,
+            viewport: None,
+            scissor: None,
+            spectrogram_texture,
+            spectrogram_cursor: 0,
+            capabilities,
+            last_uniforms: None,
         })
     }
 
+    /// Rebuild all pipelines against the current surface format
+    pub fn rebuild_pipelines(&mut self) {
+        self.pipelines = Pipelines::new(
+            &self.device,
+            &self.shader,
+            &self.pipeline_layout,
+            &self.history_pipeline_layout,
+            self.config.format,
+            self.pipeline_options(),
+        );
+    }
+
+    /// Draw the wave mesh as a wireframe (needs `POLYGON_MODE_LINE`)
+    ///
+    /// Without device support this logs a warning and keeps filled triangles.
+    pub fn set_polygon_mode(&mut self, wireframe: bool) -> Result<(), OscError> {
+        if wireframe && !self.device.features().contains(wgpu::Features::POLYGON_MODE_LINE) {
+            log::warn!("⚠️ Wireframe unsupported on this device (no POLYGON_MODE_LINE)");
+            return Ok(());
+        }
+        if wireframe != self.wireframe {
+            self.wireframe = wireframe;
+            self.rebuild_pipelines();
+        }
+        Ok(())
+    }
+
+    /// Set the background color from sRGB components (0.0 - 1.0), as picked in a color picker
+    pub fn set_background(&mut self, r: f32, g: f32, b: f32) {
+        let color = [r, g, b, 1.0].map(|channel| channel.clamp(0.0, 1.0));
+        (self.clear_color, self.background) = background_for(Some(color), self.config.format);
+    }
+
+    /// Blend the scene and particles over the target: 0 opaque, 1 alpha (default), 2 additive
+    ///
+    /// Opaque skips blending work for modes that cover every pixel; additive suits glow
+    /// and particles.
+    pub fn set_blend(&mut self, mode: u32) -> Result<(), OscError> {
+        let blend = match mode {
+            0 => None,
+            1 => Some(wgpu::BlendState::ALPHA_BLENDING),
+            2 => Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::SrcAlpha,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+            }),
+            _ => return Err(OscError::InvalidParam(format!("Invalid blend mode: {}", mode))),
+        };
+        if blend != self.blend {
+            self.blend = blend;
+            self.rebuild_pipelines();
+        }
+        Ok(())
+    }
+
+    /// Current settings for `Pipelines::new`
+    fn pipeline_options(&self) -> PipelineOptions {
+        PipelineOptions {
+            scene_polygon_mode: if self.wireframe { wgpu::PolygonMode::Line } else { wgpu::PolygonMode::Fill },
+            blend: self.blend,
+            msaa_samples: self.msaa_samples,
+        }
+    }
+
+    /// Recompile the shader from WGSL `source` and rebuild the pipelines
+    ///
+    /// On a compilation or validation error the previous shader stays active.
+    pub async fn reload_shader(&mut self, source: &str) -> Result<(), OscError> {
+        self.device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let shader = self.device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Wave Shader"),
+            source: wgpu::ShaderSource::Wgsl(source.into()),
+        });
+        let pipelines = Pipelines::new(
+            &self.device,
+            &shader,
+            &self.pipeline_layout,
+            &self.history_pipeline_layout,
+            self.config.format,
+            self.pipeline_options(),
+        );
+        if let Some(error) = self.device.pop_error_scope().await {
+            return Err(OscError::ShaderError(format!("Shader reload failed: {}", error)));
+        }
+
+        self.shader = shader;
+        self.pipelines = pipelines;
+        log::info!("🔁 Shader reloaded");
+        Ok(())
+    }
+
+    /// Whether the adapter supports everything `mode` needs
+    pub fn supports_mode(&self, mode: WaveMode) -> bool {
+        self.capabilities.covers(&mode_requirements(mode))
+    }
+
+    /// Limits the device was created with
+    pub fn limits(&self) -> wgpu::Limits {
+        self.device.limits()
+    }
+
+    /// Optional features enabled on the device
+    pub fn features(&self) -> wgpu::Features {
+        self.device.features()
+    }
+
+    /// Downlevel capabilities reported by the adapter
+    pub fn downlevel_flags(&self) -> wgpu::DownlevelFlags {
+        self.capabilities.downlevel_flags
+    }
+
+    /// MSAA sample count in use (1 when disabled)
+    pub fn msaa_samples(&self) -> u32 {
+        self.msaa_samples
+    }
+
+    /// Set the resolution multiplier the scene renders at (clamped to 0.25 - 2.0)
+    ///
+    /// Below 1.0 trades sharpness for fill rate: the scene is drawn offscreen at
+    /// `size * scale` and upscaled to the output with linear filtering.
+    pub fn set_render_scale(&mut self, scale: f32) {
+        let scale = if scale.is_finite() { scale.clamp(0.25, 2.0) } else { 1.0 };
+        if scale != self.render_scale {
+            self.render_scale = scale;
+            log::info!("🔍 Render scale set to {:.2}", scale);
+        }
+    }
+
+    /// Current render scale
+    pub fn render_scale(&self) -> f32 {
+        self.render_scale
+    }
+
+    /// Size of the history textures at the current output size and render scale
+    fn history_size(&self, pixelate: u32) -> (u32, u32) {
+        let max_dimension = self.device.limits().max_texture_dimension_2d;
+        scaled_history_size(self.size, self.render_scale, pixelate, max_dimension)
+    }
+
+    /// Create the ping-pong history textures for the given pixelation at the current size
+    ///
+    /// Pixelated targets sample with nearest filtering, plain scaled ones with linear.
+    fn create_history_targets(&self, pixelate: u32, persistent: bool) -> HistoryTargets {
+        let size = self.history_size(pixelate);
+        let sampler = if pixelate > 1 { &self.history_sampler } else { &self.scaled_sampler };
+        let create_texture = || {
+            self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("History Texture"),
+                size: wgpu::Extent3d {
+                    width: size.0,
+                    height: size.1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: self.config.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING
+                    | wgpu::TextureUsages::COPY_SRC,
+                view_formats: &[],
+            })
+        };
+        let textures = [create_texture(), create_texture()];
+        let views = [
+            textures[0].create_view(&wgpu::TextureViewDescriptor::default()),
+            textures[1].create_view(&wgpu::TextureViewDescriptor::default()),
+        ];
+        let create_bind_group = |view: &wgpu::TextureView| {
+            self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+                label: Some("History Texture Bind Group"),
+                layout: &self.texture_bind_group_layout,
+                entries: &[
+                    wgpu::BindGroupEntry {
+                        binding: 0,
+                        resource: wgpu::BindingResource::TextureView(view),
+                    },
+                    wgpu::BindGroupEntry {
+                        binding: 1,
+                        resource: wgpu::BindingResource::Sampler(sampler),
+                    },
+                ],
+            })
+        };
+        let bind_groups = [create_bind_group(&views[0]), create_bind_group(&views[1])];
+
+        HistoryTargets {
+            pixelate,
+            size,
+            persistent,
+            textures,
+            views,
+            bind_groups,
+            current: 0,
+        }
+    }
+
+    /// Create the multisampled color target matching the surface, or None without MSAA
+    fn create_msaa_target(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        samples: u32,
+    ) -> Option<(wgpu::Texture, wgpu::TextureView)> {
+        if samples <= 1 {
+            return None;
+        }
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("MSAA Color Texture"),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: samples,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        Some((texture, view))
+    }
+
+    /// Create, resize or drop the history textures to match persistence, pixelation and render scale
+    fn update_history_targets(&mut self, params: &WaveParams) {
+        let persistent = params.persistence > 0.0;
+        let pixelate = params.pixelate.max(1);
+        if !persistent && pixelate == 1 && self.render_scale == 1.0 {
+            self.history = None;
+            return;
+        }
+
+        let size = self.history_size(pixelate);
+        match self.history {
+            Some(ref mut targets) if targets.pixelate == pixelate && targets.size == size => {
+                targets.persistent = persistent
+            }
+            _ => self.history = Some(self.create_history_targets(pixelate, persistent)),
+        }
+    }
+
+    /// Create vertex and index buffers for a fullscreen quad subdivided `n x n` times
+    ///
+    /// The vertex buffer accepts writes so CPU-computed heights can be uploaded per frame.
+    fn create_grid_buffers(device: &wgpu::Device, n: u32) -> (wgpu::Buffer, wgpu::Buffer, u32) {
+        let mut vertices = Vec::with_capacity(((n + 1) * (n + 1)) as usize);
+        for row in 0..=n {
+            for col in 0..=n {
+                let u = col as f32 / n as f32;
+                let v = row as f32 / n as f32;
+                vertices.push(Vertex {
+                    position: [u * 2.0 - 1.0, v * 2.0 - 1.0, 0.0],
+                    uv: [u, 1.0 - v],
+                });
+            }
+        }
+
+        let mut indices: Vec<u16> = Vec::with_capacity((n * n * 6) as usize);
+        for row in 0..n {
+            for col in 0..n {
+                let bottom_left = (row * (n + 1) + col) as u16;
+                let top_left = bottom_left + (n + 1) as u16;
+                indices.extend_from_slice(&[
+                    bottom_left,
+                    bottom_left + 1,
+                    top_left + 1,
+                    top_left + 1,
+                    top_left,
+                    bottom_left,
+                ]);
+            }
+        }
+
+        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Vertex Buffer"),
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let index_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        });
+
+        (vertex_buffer, index_buffer, indices.len() as u32)
+    }
+
+    /// Subdivide the fullscreen quad into an `n x n` grid (1 = plain quad, max 255 for 16-bit indices)
+    pub fn set_geometry_mode(&mut self, grid_n: u32) {
+        let grid_n = grid_n.clamp(1, 255);
+        if grid_n != self.grid_size {
+            let (vertex_buffer, index_buffer, num_indices) = Self::create_grid_buffers(&self.device, grid_n);
+            self.vertex_buffer = vertex_buffer;
+            self.index_buffer = index_buffer;
+            self.num_indices = num_indices;
+            self.grid_size = grid_n;
+            log::info!("🔲 Geometry grid set to {}x{}", grid_n, grid_n);
+        }
+    }
+
+    fn create_particle_buffer(device: &wgpu::Device, count: u32) -> wgpu::Buffer {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Particle Buffer"),
+            contents: bytemuck::cast_slice(&Particle::scatter(count)),
+            usage: wgpu::BufferUsages::VERTEX,
+        })
+    }
+
+    /// Rebuild the particle buffer with `count` particles
+    pub fn set_particle_count(&mut self, count: u32) {
+        let count = count.max(1);
+        if count != self.num_particles {
+            self.particle_buffer = Self::create_particle_buffer(&self.device, count);
+            self.num_particles = count;
+            log::info!("✨ Particle count set to {}", count);
+        }
+    }
+
+    fn create_bar_buffer(device: &wgpu::Device, count: u32) -> wgpu::Buffer {
+        device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Bar Instance Buffer"),
+            size: (count as usize * std::mem::size_of::<Bar>()) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        })
+    }
+
+    /// Set the `WaveMode::Bars3D` grid: `cols` bars across the bands, `rows` frames of history deep
+    pub fn set_bar_grid(&mut self, cols: u32, rows: u32) {
+        let grid = (cols.clamp(1, MAX_BAR_GRID), rows.clamp(1, MAX_BAR_GRID));
+        if grid != self.bar_grid {
+            self.bar_grid = grid;
+            self.bar_heights = vec![0.0; (grid.0 * grid.1) as usize];
+            self.bar_buffer = Self::create_bar_buffer(&self.device, grid.0 * grid.1);
+            log::info!("📊 Bar grid set to {}x{}", grid.0, grid.1);
+        }
+    }
+
+    /// Scroll the bars back a row, add `bands` as the front row and upload the grid
+    fn update_bars(&mut self, params: &WaveParams, bands: &[f32]) {
+        let (cols, rows) = self.bar_grid;
+        let cols = cols as usize;
+        self.bar_heights.rotate_right(cols);
+        for (col, height) in self.bar_heights[..cols].iter_mut().enumerate() {
+            // Interpolate so wide grids don't stair-step across the few bands
+            let level = match bands.len() {
+                0 => 0.0,
+                len => {
+                    let position = col as f32 * (len - 1) as f32 / (cols - 1).max(1) as f32;
+                    let lower = position as usize;
+                    let upper = (lower + 1).min(len - 1);
+                    bands[lower] + (bands[upper] - bands[lower]) * (position - lower as f32)
+                }
+            };
+            *height = (level * params.amplitude).clamp(0.0, 2.0);
+        }
+        let bars = Bar::grid(cols as u32, rows, &self.bar_heights);
+        self.queue.write_buffer(&self.bar_buffer, 0, bytemuck::cast_slice(&bars));
+
+        let size = self.scene_target_size();
+        let stale = self
+            .depth_target
+            .as_ref()
+            .is_none_or(|(texture, _)| (texture.width(), texture.height()) != size);
+        if stale {
+            let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("Depth Texture"),
+                size: wgpu::Extent3d {
+                    width: size.0,
+                    height: size.1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: DEPTH_FORMAT,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+                view_formats: &[],
+            });
+            let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+            self.depth_target = Some((texture, view));
+        }
+    }
+
+    /// Upload interleaved (side, mid) points for `WaveMode::Goniometer`
+    ///
+    /// Points beyond `GONIOMETER_POINTS` are dropped.
+    pub fn update_goniometer(&mut self, points: &[f32]) {
+        let count = (points.len() / 2).min(GONIOMETER_POINTS);
+        if count > 0 {
+            self.queue.write_buffer(&self.goniometer_buffer, 0, bytemuck::cast_slice(&points[..count * 2]));
+        }
+        self.goniometer_count = count as u32;
+    }
+
+    /// Wipe the persistence trails on the next frame, whatever the decay setting
+    pub fn clear_history(&mut self) {
+        self.clear_history = true;
+    }
+
+    /// Size of the texture the scene passes draw into: the history target or the output
+    fn scene_target_size(&self) -> (u32, u32) {
+        match self.history {
+            Some(ref targets) => targets.size,
+            None => self.size,
+        }
+    }
+
+    /// Write `spectrum` (0.0 - 1.0, bass first) as the newest spectrogram column
+    ///
+    /// The spectrum is resampled to `SPECTROGRAM_BANDS` rows.
+    pub fn push_spectrogram_column(&mut self, spectrum: &[f32]) {
+        if spectrum.is_empty() {
+            return;
+        }
+        let column: Vec<u8> = (0..SPECTROGRAM_BANDS)
+            .map(|row| {
+                let level = spectrum[row * spectrum.len() / SPECTROGRAM_BANDS];
+                (level.clamp(0.0, 1.0) * 255.0).round() as u8
+            })
+            .collect();
+
+        self.queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.spectrogram_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: self.spectrogram_cursor,
+                    y: 0,
+                    z: 0,
+                },
+                aspect: wgpu::TextureAspect::All,
+            },
+            &column,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(1),
+                rows_per_image: Some(SPECTROGRAM_BANDS as u32),
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: SPECTROGRAM_BANDS as u32,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.spectrogram_cursor = (self.spectrogram_cursor + 1) % SPECTROGRAM_COLUMNS;
+    }
+
     /// Resize the renderer
-    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), JsValue> {
+    ///
+    /// The new size is applied at the start of the next frame, so a burst of resize
+    /// events (dragging the window edge) reconfigures the surface only once.
+    pub fn resize(&mut self, width: u32, height: u32) -> Result<(), OscError> {
         if width > 0 && height > 0 {
+            self.pending_size = Some((width, height));
+        }
+        Ok(())
+    }
+
+    /// Reconfigure the output and size-dependent targets for a size stored by `resize`
+    fn apply_pending_resize(&mut self) {
+        let Some((width, height)) = self.pending_size.take() else {
+            return;
+        };
+        if (width, height) != self.size {
             self.size = (width, height);
             self.config.width = width;
             self.config.height = height;
-            self.surface.configure(&self.device, &self.config);
+            match self.output {
+                RenderOutput::Surface(ref surface) => surface.configure(&self.device, &self.config),
+                RenderOutput::Texture(ref mut texture) => {
+                    *texture = Self::create_capture_texture(&self.device, &self.config, "Headless Output Texture");
+                }
+            }
+            self.msaa_target = Self::create_msaa_target(&self.device, &self.config, self.msaa_samples);
+            if let Some(ref targets) = self.history {
+                self.history = Some(self.create_history_targets(targets.pixelate, targets.persistent));
+            }
             log::info!("📐 Resized to {}x{}", width, height);
         }
+    }
+
+    /// Draw into the `w x h` region at `(x, y)` (pixels from the top-left) instead of the whole output
+    ///
+    /// The region is clipped to the output when drawing. The output is still cleared
+    /// everywhere unless `set_scissor` is also used.
+    pub fn set_viewport(&mut self, x: f32, y: f32, w: f32, h: f32) -> Result<(), OscError> {
+        if ![x, y, w, h].iter().all(|value| value.is_finite()) || w <= 0.0 || h <= 0.0 {
+            return Err(OscError::InvalidParam(format!("Invalid viewport: {}x{} at ({}, {})", w, h, x, y)));
+        }
+        self.viewport = Some([x, y, w, h]);
         Ok(())
     }
 
+    /// Restrict output writes to the `w x h` rectangle at `(x, y)`, leaving the rest untouched
+    ///
+    /// With a scissor the output is loaded instead of cleared, but a canvas hands out a
+    /// fresh, cleared texture every frame: the rest of it only keeps what was drawn into
+    /// that same texture earlier in the same frame, not the previous frame's panels.
+    pub fn set_scissor(&mut self, x: u32, y: u32, w: u32, h: u32) {
+        self.scissor = Some([x, y, w, h]);
+    }
+
+    /// Draw to and clear the whole output again
+    pub fn reset_viewport(&mut self) {
+        self.viewport = None;
+        self.scissor = None;
+    }
+
+    /// The viewport clipped to the output, or None when it's unset or entirely outside
+    fn clipped_viewport(&self) -> Option<[f32; 4]> {
+        let [x, y, w, h] = self.viewport?;
+        let (width, height) = (self.size.0 as f32, self.size.1 as f32);
+        let (left, top) = (x.clamp(0.0, width), y.clamp(0.0, height));
+        let (right, bottom) = ((x + w).clamp(0.0, width), (y + h).clamp(0.0, height));
+        (right > left && bottom > top).then_some([left, top, right - left, bottom - top])
+    }
+
     /// Render a frame
-    pub fn render(&mut self, time: f32, params: &WaveParams) -> Result<(), JsValue> {
+    pub fn render(&mut self, time: f32, params: &WaveParams, bands: &[f32]) -> Result<(), OscError> {
+        self.apply_pending_resize();
+        if self.size.0 == 0 || self.size.1 == 0 {
+            return Ok(());
+        }
+        self.write_uniforms(time, params, bands);
+        self.update_history_targets(params);
+
+        // Get current texture; headless renderers draw into their own
+        let (frame, view) = match self.output {
+            RenderOutput::Surface(ref surface) => {
+                let frame = surface.get_current_texture()?;
+                let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+                (Some(frame), view)
+            }
+            RenderOutput::Texture(ref texture) => {
+                (None, texture.create_view(&wgpu::TextureViewDescriptor::default()))
+            }
+        };
+
+        // Create command encoder
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Render Encoder"),
+        });
+
+        // Encode each pass of the frame in order
+        let passes = self.frame_passes(params);
+        if passes.contains(&FramePass::Bars) {
+            self.update_bars(params, bands);
+        }
+        self.encode_passes(&passes, &mut encoder, &view);
+
+        // Submit commands
+        self.queue.submit(std::iter::once(encoder.finish()));
+        if let Some(frame) = frame {
+            frame.present();
+        }
+        self.swap_history_targets();
+        self.last_passes = passes;
+        self.clear_history = false;
+
+        Ok(())
+    }
+
+    /// Render a frame into an offscreen texture and read back its RGBA pixels
+    pub async fn capture_frame(&mut self, time: f32, params: &WaveParams, bands: &[f32]) -> Result<Vec<u8>, OscError> {
+        self.apply_pending_resize();
+        self.write_uniforms(time, params, bands);
+        self.update_history_targets(params);
+
+        let texture = Self::create_capture_texture(&self.device, &self.config, "Capture Texture");
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Capture Encoder"),
+        });
+        let passes = self.frame_passes(params);
+        if passes.contains(&FramePass::Bars) {
+            self.update_bars(params, bands);
+        }
+        self.encode_passes(&passes, &mut encoder, &view);
+        self.queue.submit(std::iter::once(encoder.finish()));
+        self.swap_history_targets();
+        self.last_passes = passes;
+        self.clear_history = false;
+
+        self.read_texture(&texture).await
+    }
+
+    /// Read back the RGBA pixels of the last frame rendered by a headless renderer
+    pub async fn read_frame(&self) -> Result<Vec<u8>, OscError> {
+        match self.output {
+            RenderOutput::Texture(ref texture) => self.read_texture(texture).await,
+            RenderOutput::Surface(_) => Err(OscError::Unsupported(
+                "read_frame needs a headless renderer; use capture_frame".to_string(),
+            )),
+        }
+    }
+
+    /// Read back the RGBA color of the most recent frame at `(x, y)`
+    ///
+    /// Copied from the history texture when there is one, else the frame is re-rendered
+    /// offscreen with its uniforms; either way the animation and history aren't advanced.
+    pub async fn read_pixel(&mut self, x: u32, y: u32) -> Result<[u8; 4], OscError> {
+        let (width, height) = self.size;
+        if x >= width || y >= height {
+            return Err(OscError::InvalidParam(format!(
+                "Pixel ({}, {}) is outside the {}x{} canvas",
+                x, y, width, height
+            )));
+        }
+        if self.last_passes.is_empty() {
+            return Err(OscError::InvalidState("No frame has been rendered yet".to_string()));
+        }
+
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pixel Readback Buffer"),
+            size: 4,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Pixel Readback Encoder"),
+        });
+        // The history target drawn last already holds the frame; replaying the passes
+        // would fade it a second time. Without history the frame is redrawn.
+        let capture;
+        let (texture, origin) = match self.history {
+            Some(ref targets) => {
+                let (history_width, history_height) = targets.size;
+                let origin = wgpu::Origin3d {
+                    x: (x as u64 * history_width as u64 / width as u64) as u32,
+                    y: (y as u64 * history_height as u64 / height as u64) as u32,
+                    z: 0,
+                };
+                (&targets.textures[1 - targets.current], origin)
+            }
+            None => {
+                capture = Self::create_capture_texture(&self.device, &self.config, "Pixel Capture Texture");
+                let view = capture.create_view(&wgpu::TextureViewDescriptor::default());
+                self.encode_passes(&self.last_passes, &mut encoder, &view);
+                (&capture, wgpu::Origin3d { x, y, z: 0 })
+            }
+        };
+        // A single row needs no bytes-per-row alignment
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &readback_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: None,
+                    rows_per_image: None,
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let data = self.read_buffer(&readback_buffer).await?;
+        let mut pixel = [data[0], data[1], data[2], data[3]];
+        if self.is_bgra() {
+            pixel.swap(0, 2);
+        }
+        Ok(pixel)
+    }
+
+    /// Create a single-sampled, copyable texture matching the output configuration
+    fn create_capture_texture(
+        device: &wgpu::Device,
+        config: &wgpu::SurfaceConfiguration,
+        label: &str,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some(label),
+            size: wgpu::Extent3d {
+                width: config.width,
+                height: config.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: config.format,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            view_formats: &[],
+        })
+    }
+
+    /// Copy an output-sized texture into a readback buffer and return its RGBA pixels
+    async fn read_texture(&self, texture: &wgpu::Texture) -> Result<Vec<u8>, OscError> {
+        let (width, height) = self.size;
+
+        // Rows in the readback buffer must be padded to the copy alignment
+        let row_bytes = width * 4;
+        let padded_row_bytes = row_bytes.div_ceil(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+            * wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let readback_buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Capture Readback Buffer"),
+            size: (padded_row_bytes * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Readback Encoder"),
+        });
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &readback_buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_row_bytes),
+                    rows_per_image: Some(height),
+                },
+            },
+            wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+        );
+        self.queue.submit(std::iter::once(encoder.finish()));
+
+        let padded = self.read_buffer(&readback_buffer).await?;
+
+        // Strip row padding and convert to RGBA
+        let mut pixels = Vec::with_capacity((row_bytes * height) as usize);
+        for row in padded.chunks(padded_row_bytes as usize) {
+            pixels.extend_from_slice(&row[..row_bytes as usize]);
+        }
+        if self.is_bgra() {
+            for pixel in pixels.chunks_mut(4) {
+                pixel.swap(0, 2);
+            }
+        }
+
+        Ok(pixels)
+    }
+
+    /// Whether the surface stores pixels as BGRA and readbacks need swizzling
+    fn is_bgra(&self) -> bool {
+        matches!(
+            self.config.format,
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb
+        )
+    }
+
+    /// Map a `MAP_READ` buffer and copy out its contents
+    async fn read_buffer(&self, buffer: &wgpu::Buffer) -> Result<Vec<u8>, OscError> {
+        let slice = buffer.slice(..);
+        let status = Arc::new(Mutex::new(None));
+        let callback_status = Arc::clone(&status);
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            if let Ok(mut status) = callback_status.lock() {
+                *status = Some(result);
+            }
+        });
+        // Native backends can block until the mapping completes; the browser needs the event loop
+        #[cfg(not(target_arch = "wasm32"))]
+        self.device.poll(wgpu::Maintain::Wait);
+
+        loop {
+            self.device.poll(wgpu::Maintain::Poll);
+            let result = status.lock().ok().and_then(|mut status| status.take());
+            if let Some(result) = result {
+                result.map_err(|e| OscError::InvalidState(format!("Failed to map buffer: {}", e)))?;
+                break;
+            }
+            next_tick()
+                .await
+                .map_err(|_| OscError::Environment("Timer callback failed".to_string()))?;
+        }
+
+        let data = slice.get_mapped_range().to_vec();
+        buffer.unmap();
+        Ok(data)
+    }
+
+    /// Upload per-frame uniforms
+    fn write_uniforms(&mut self, time: f32, params: &WaveParams, bands: &[f32]) {
+        // Pack frequency bands into vec4 slots
+        let mut packed_bands = [[0.0; 4]; NUM_BANDS / 4];
+        for (i, &band) in bands.iter().take(NUM_BANDS).enumerate() {
+            packed_bands[i / 4][i % 4] = band;
+        }
+
         // Update uniforms
         let uniforms = Uniforms {
             time,
             amplitude: params.amplitude,
             frequency: params.frequency,
             speed: params.speed,
-            resolution: [self.size.0 as f32, self.size.1 as f32],
+            resolution: match self.clipped_viewport() {
+                Some([_, _, w, h]) => [w, h],
+                None => [self.size.0 as f32, self.size.1 as f32],
+            },
             hue: params.hue,
             mode: params.mode as u32,
+            target_mode: params.target_mode as u32,
+            mix: params.mix,
+            symmetry: params.symmetry,
+            polar: params.polar as u32,
+            bands: packed_bands,
+            line_width: params.line_width,
+            exposure: params.exposure,
+            gamma: params.gamma,
+            grid: params.grid,
+            hue2: params.hue2,
+            saturation: params.saturation,
+            lightness: params.lightness,
+            camera_offset: params.camera_offset,
+            persistence: params.persistence,
+            color_by_frequency: params.color_by_frequency as u32,
+            layers: params.layers,
+            aberration: params.aberration,
+            mouse: params.mouse,
+            intensity_from_amplitude: params.intensity_from_amplitude,
+            spectrogram_cursor: self.spectrogram_cursor as f32 / SPECTROGRAM_COLUMNS as f32,
+            phase: params.phase,
+            transparent: self.transparent as u32,
+            pulse_phase: params.pulse.unwrap_or(0.0),
+            pulse_enabled: params.pulse.is_some() as u32,
+            highlight_band: params.highlight_band,
+            bar_columns: self.bar_grid.0,
+            background: self.background,
+            ..Default::default()
         };
-        self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
 
-        // Get current texture
-        let output = self.surface.get_current_texture()
-            .map_err(|e| JsValue::from_str(&format!("Failed to get surface texture: {}", e)))?;
-        
-        let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        // Only upload when something changed (time stands still while paused)
+        let dirty = self
+            .last_uniforms
+            .is_none_or(|last| bytemuck::bytes_of(&last) != bytemuck::bytes_of(&uniforms));
+        if dirty {
+            self.queue.write_buffer(&self.uniform_buffer, 0, bytemuck::cast_slice(&[uniforms]));
+            self.last_uniforms = Some(uniforms);
+        }
+    }
+
+    /// Make this frame's history texture the one faded from next frame
+    fn swap_history_targets(&mut self) {
+        if let Some(ref mut targets) = self.history {
+            targets.current = 1 - targets.current;
+        }
+    }
 
-        // Create command encoder
-        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
-            label: Some("Render Encoder"),
-        });
+    /// Passes making up a frame for the given params, in encoding order
+    ///
+    /// While cross-fading, both modes' passes are drawn and the shaders weight each by
+    /// `mix`; the fullscreen scene then also lays down the background.
+    fn frame_passes(&self, params: &WaveParams) -> Vec<FramePass> {
+        let mode_pass = |mode| match mode {
+            WaveMode::Particles => FramePass::Particles,
+            WaveMode::Bars3D => FramePass::Bars,
+            WaveMode::Goniometer => FramePass::Goniometer,
+            _ => FramePass::Scene,
+        };
 
-        // Begin render pass
-        {
-            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
-                label: Some("Wave Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: 0.02,
-                            g: 0.02,
-                            b: 0.05,
-                            a: 1.0,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                })],
-                depth_stencil_attachment: None,
-                occlusion_query_set: None,
-                timestamp_writes: None,
-            });
+        let mut passes = if params.mix <= 0.0 {
+            vec![mode_pass(params.mode)]
+        } else if params.mix >= 1.0 {
+            vec![mode_pass(params.target_mode)]
+        } else {
+            vec![FramePass::Scene, mode_pass(params.mode), mode_pass(params.target_mode)]
+        };
+        if self.history.is_some() {
+            passes.extend([FramePass::Fade, FramePass::Blit]);
+        }
+        passes.sort();
+        passes.dedup();
+        passes
+    }
 
-            render_pass.set_pipeline(&self.render_pipeline);
-            render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
-            render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
-            render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
-            render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+    /// Record `passes` in order; only the first pass reaching the output clears it
+    fn encode_passes(&self, passes: &[FramePass], encoder: &mut wgpu::CommandEncoder, output_view: &wgpu::TextureView) {
+        let mut output_drawn = false;
+        for &pass in passes {
+            self.encode_pass(pass, encoder, output_view, output_drawn);
+            output_drawn |= pass == FramePass::Blit || self.history.is_none();
         }
+    }
 
-        // Submit commands
-        self.queue.submit(std::iter::once(encoder.finish()));
-        output.present();
+    /// Record a single pass into the frame's command encoder
+    ///
+    /// With history targets active, every pass but the blit draws into the history texture.
+    /// Without persistence the fade clears it to the background.
+    fn encode_pass(
+        &self,
+        pass: FramePass,
+        encoder: &mut wgpu::CommandEncoder,
+        output_view: &wgpu::TextureView,
+        output_drawn: bool,
+    ) {
+        let clear = wgpu::LoadOp::Clear(self.clear_color);
+        let to_output = pass == FramePass::Blit || self.history.is_none();
+        // A scissored output keeps whatever lies outside the region, and later passes
+        // of a cross-fade draw over the earlier ones
+        let output_load = if self.scissor.is_some() || output_drawn { wgpu::LoadOp::Load } else { clear };
+        let (view, load) = match (pass, &self.history) {
+            (FramePass::Blit, _) | (_, None) => (output_view, output_load),
+            (FramePass::Fade, Some(targets)) => (&targets.views[targets.current], clear),
+            (_, Some(targets)) => (&targets.views[targets.current], wgpu::LoadOp::Load),
+        };
 
-        Ok(())
+        // Without history targets the scene goes straight to the output, multisampled if enabled
+        let msaa = match (&self.msaa_target, &self.history) {
+            (Some((_, msaa_view)), None) if !matches!(pass, FramePass::Bars | FramePass::Goniometer) => {
+                Some(msaa_view)
+            }
+            _ => None,
+        };
+        let depth_stencil_attachment = match (pass, &self.depth_target) {
+            (FramePass::Bars, Some((_, depth_view))) => Some(wgpu::RenderPassDepthStencilAttachment {
+                view: depth_view,
+                depth_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(1.0),
+                    store: wgpu::StoreOp::Discard,
+                }),
+                stencil_ops: None,
+            }),
+            _ => None,
+        };
+        let (view, resolve_target) = match msaa {
+            Some(msaa_view) => (msaa_view, Some(view)),
+            None => (view, None),
+        };
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some(pass.label()),
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view,
+                resolve_target,
+                ops: wgpu::Operations {
+                    load,
+                    store: wgpu::StoreOp::Store,
+                },
+            })],
+            depth_stencil_attachment,
+            occlusion_query_set: None,
+            timestamp_writes: None,
+        });
+
+        if to_output {
+            if self.viewport.is_some() {
+                let Some([x, y, w, h]) = self.clipped_viewport() else {
+                    return;
+                };
+                render_pass.set_viewport(x, y, w, h, 0.0, 1.0);
+            }
+            if let Some([x, y, w, h]) = self.scissor {
+                let (x, y) = (x.min(self.size.0), y.min(self.size.1));
+                render_pass.set_scissor_rect(x, y, w.min(self.size.0 - x), h.min(self.size.1 - y));
+            }
+        }
+
+        match pass {
+            FramePass::Fade | FramePass::Blit => {
+                let Some(ref targets) = self.history else {
+                    return;
+                };
+                // A forced clear keeps the cleared target rather than fading the old trails in
+                if pass == FramePass::Fade && self.clear_history {
+                    return;
+                }
+                // Fade reads last frame's texture, blit reads this frame's
+                let (pipeline, source) = if pass == FramePass::Fade {
+                    (&self.pipelines.fade, 1 - targets.current)
+                } else {
+                    (&self.pipelines.blit, targets.current)
+                };
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                render_pass.set_bind_group(1, &targets.bind_groups[source], &[]);
+                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+            }
+            FramePass::Scene => {
+                let persistent = self.history.as_ref().is_some_and(|targets| targets.persistent);
+                let pipeline = match (msaa, &self.pipelines.msaa_scene) {
+                    (Some(_), Some(msaa_scene)) => msaa_scene,
+                    _ if persistent => &self.pipelines.persistent_scene,
+                    _ => &self.pipelines.scene,
+                };
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.vertex_buffer.slice(..));
+                render_pass.set_index_buffer(self.index_buffer.slice(..), wgpu::IndexFormat::Uint16);
+                render_pass.draw_indexed(0..self.num_indices, 0, 0..1);
+            }
+            FramePass::Particles => {
+                let pipeline = match (msaa, &self.pipelines.msaa_particles) {
+                    (Some(_), Some(msaa_particles)) => msaa_particles,
+                    _ => &self.pipelines.particles,
+                };
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.particle_buffer.slice(..));
+                render_pass.draw(0..6, 0..self.num_particles);
+            }
+            FramePass::Bars => {
+                render_pass.set_pipeline(&self.pipelines.bars);
+                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.bar_buffer.slice(..));
+                render_pass.draw(0..36, 0..self.bar_grid.0 * self.bar_grid.1);
+            }
+            FramePass::Goniometer => {
+                render_pass.set_pipeline(&self.pipelines.goniometer);
+                render_pass.set_bind_group(0, &self.uniform_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, self.goniometer_buffer.slice(..));
+                render_pass.draw(0..self.goniometer_count, 0..1);
+            }
+        }
     }
 }
+
+/// Wait for the next browser task so pending GPU callbacks can run
+///
+/// Uses the global `setTimeout`, which Web Workers have too (they have no `window`).
+async fn next_tick() -> Result<(), JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, _reject| {
+        let global = js_sys::global();
+        let scheduled = js_sys::Reflect::get(&global, &"setTimeout".into())
+            .ok()
+            .and_then(|set_timeout| set_timeout.dyn_into::<js_sys::Function>().ok())
+            .map(|set_timeout| set_timeout.call2(&global, &resolve, &JsValue::from(0)).is_ok())
+            .unwrap_or(false);
+        if !scheduled {
+            let _ = resolve.call0(&JsValue::NULL);
+        }
+    });
+    wasm_bindgen_futures::JsFuture::from(promise).await.map(|_| ())
+}
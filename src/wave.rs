@@ -2,12 +2,19 @@
 
 use wasm_bindgen::prelude::*;
 
+use crate::palette::{hsl_to_rgb, wrap_hue};
+
 /// Wave visualization modes
+///
+/// Serializes as its `u32` discriminant, matching the JS-facing `mode` values.
 #[wasm_bindgen]
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "u32", from = "u32"))]
 #[repr(u32)]
 pub enum WaveMode {
     /// Classic sine wave visualization
+    #[default]
     SineWaves = 0,
     /// Circular ripple patterns from center
     CircularRipples = 1,
@@ -17,9 +24,54 @@ pub enum WaveMode {
     PlasmaField = 3,
     /// 3D wave surface perspective
     WaveSurface = 4,
+    /// Band-reactive particle field
+    Particles = 5,
+    /// Infinite-zoom tunnel of scrolling rings
+    Tunnel = 6,
+    /// Scrolling waterfall of the spectrum history
+    Spectrogram = 7,
+    /// Isometric grid of bars rising with the bands, scrolling back over time
+    Bars3D = 8,
+    /// Stereo vectorscope: mid/side sample pairs traced as a line (pair with persistence for trails)
+    Goniometer = 9,
 }
 
 impl WaveMode {
+    /// Every mode, in discriminant order
+    pub const ALL: [WaveMode; 10] = [
+        WaveMode::SineWaves,
+        WaveMode::CircularRipples,
+        WaveMode::LissajousCurves,
+        WaveMode::PlasmaField,
+        WaveMode::WaveSurface,
+        WaveMode::Particles,
+        WaveMode::Tunnel,
+        WaveMode::Spectrogram,
+        WaveMode::Bars3D,
+        WaveMode::Goniometer,
+    ];
+
+    /// Ids of every mode, in order
+    pub fn all() -> Vec<u32> {
+        Self::ALL.iter().map(|&mode| mode as u32).collect()
+    }
+
+    /// Display name of the mode
+    pub fn name(self) -> &'static str {
+        match self {
+            WaveMode::SineWaves => "Sine Waves",
+            WaveMode::CircularRipples => "Circular Ripples",
+            WaveMode::LissajousCurves => "Lissajous Curves",
+            WaveMode::PlasmaField => "Plasma Field",
+            WaveMode::WaveSurface => "Wave Surface",
+            WaveMode::Particles => "Particles",
+            WaveMode::Tunnel => "Tunnel",
+            WaveMode::Spectrogram => "Spectrogram",
+            WaveMode::Bars3D => "Bars 3D",
+            WaveMode::Goniometer => "Goniometer",
+        }
+    }
+
     pub fn from_u32(value: u32) -> Self {
         match value {
             0 => WaveMode::SineWaves,
@@ -27,20 +79,61 @@ impl WaveMode {
             2 => WaveMode::LissajousCurves,
             3 => WaveMode::PlasmaField,
             4 => WaveMode::WaveSurface,
+            5 => WaveMode::Particles,
+            6 => WaveMode::Tunnel,
+            7 => WaveMode::Spectrogram,
+            8 => WaveMode::Bars3D,
+            9 => WaveMode::Goniometer,
             _ => WaveMode::SineWaves,
         }
     }
+
+    /// Parameters this mode looks best with
+    pub fn default_params(self) -> WaveParams {
+        let (amplitude, frequency, speed) = match self {
+            WaveMode::SineWaves => (1.0, 3.0, 1.0),
+            WaveMode::CircularRipples => (0.8, 1.5, 0.8),
+            WaveMode::LissajousCurves => (1.0, 1.2, 0.6),
+            WaveMode::PlasmaField => (1.2, 2.5, 0.5),
+            WaveMode::WaveSurface => (1.0, 2.0, 0.8),
+            WaveMode::Particles => (1.0, 2.0, 1.0),
+            WaveMode::Tunnel => (1.0, 1.5, 0.7),
+            WaveMode::Spectrogram => (1.0, 1.0, 1.0),
+            WaveMode::Bars3D => (1.0, 1.0, 1.0),
+            WaveMode::Goniometer => (1.0, 1.0, 1.0),
+        };
+
+        WaveParams {
+            amplitude,
+            frequency,
+            speed,
+            mode: self,
+            target_mode: self,
+            ..WaveParams::default()
+        }
+    }
 }
 
-impl Default for WaveMode {
-    fn default() -> Self {
-        WaveMode::SineWaves
+impl From<u32> for WaveMode {
+    fn from(value: u32) -> Self {
+        WaveMode::from_u32(value)
+    }
+}
+
+impl From<WaveMode> for u32 {
+    fn from(mode: WaveMode) -> Self {
+        mode as u32
     }
 }
 
 /// Parameters controlling wave visualization
+///
+/// With the `serde` feature, missing fields deserialize to their defaults and the
+/// per-frame derived fields (camera offset, pulse) are skipped.
 #[wasm_bindgen]
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct WaveParams {
     /// Wave amplitude (height)
     pub amplitude: f32,
@@ -52,6 +145,57 @@ pub struct WaveParams {
     pub hue: f32,
     /// Visualization mode
     pub mode: WaveMode,
+    /// Mode being cross-faded toward during a transition
+    pub target_mode: WaveMode,
+    /// Blend factor between `mode` (0.0) and `target_mode` (1.0)
+    pub mix: f32,
+    /// Mirror symmetry (0 none, 1 horizontal, 2 vertical, 3 quad)
+    pub symmetry: u32,
+    /// Wrap the visualization into polar coordinates
+    pub polar: bool,
+    /// Base trace width
+    pub line_width: f32,
+    /// Extra trace width added per unit of smoothed amplitude
+    pub line_width_reactive: f32,
+    /// Output exposure multiplier
+    pub exposure: f32,
+    /// Output gamma
+    pub gamma: f32,
+    /// Graticule cell count (0 disables the grid overlay)
+    pub grid: u32,
+    /// Secondary glow hue, relative to the primary hue (degrees)
+    pub hue2: f32,
+    /// Color saturation (0.0 - 1.0)
+    pub saturation: f32,
+    /// Color lightness (0.0 - 1.0)
+    pub lightness: f32,
+    /// View offset applied by camera effects
+    #[wasm_bindgen(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub camera_offset: [f32; 2],
+    /// Phosphor persistence: fraction of the previous frame kept (0.0 clears every frame)
+    pub persistence: f32,
+    /// Color by horizontal position / band (bass red → treble blue) instead of the global hue
+    pub color_by_frequency: bool,
+    /// Number of stacked sine layers in SineWaves mode (1-8)
+    pub layers: u32,
+    /// Render at `1 / pixelate` resolution and upscale with nearest sampling (1 = full resolution)
+    pub pixelate: u32,
+    /// Chromatic aberration strength per unit of smoothed amplitude (0.0 disables)
+    pub aberration: f32,
+    /// Pointer position, normalized 0.0 - 1.0 from the top-left (center by default)
+    #[wasm_bindgen(skip)]
+    pub mouse: [f32; 2],
+    /// How strongly large wave swings brighten toward white (0.0 keeps flat coloring)
+    pub intensity_from_amplitude: f32,
+    /// Phase offset added to the animation time term (radians)
+    pub phase: f32,
+    /// Position through the current beat (0.0 on the beat - 1.0), None without a tempo pulse
+    #[wasm_bindgen(skip)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub pulse: Option<f32>,
+    /// Bars3D column (band) to highlight while dimming the rest, -1 for none
+    pub highlight_band: i32,
 }
 
 impl Default for WaveParams {
@@ -62,6 +206,29 @@ impl Default for WaveParams {
             speed: 1.0,
             hue: 180.0,
             mode: WaveMode::SineWaves,
+            target_mode: WaveMode::SineWaves,
+            mix: 0.0,
+            symmetry: 0,
+            polar: false,
+            line_width: 0.08,
+            line_width_reactive: 0.0,
+            exposure: 1.0,
+            gamma: 2.2,
+            grid: 0,
+            hue2: 180.0,
+            saturation: 0.9,
+            lightness: 0.5,
+            camera_offset: [0.0, 0.0],
+            persistence: 0.0,
+            color_by_frequency: false,
+            layers: DEFAULT_LAYERS,
+            pixelate: 1,
+            aberration: 0.0,
+            mouse: [0.5, 0.5],
+            intensity_from_amplitude: 0.0,
+            phase: 0.0,
+            pulse: None,
+            highlight_band: -1,
         }
     }
 }
@@ -74,21 +241,129 @@ impl WaveParams {
     }
 }
 
+/// Cross-fade between two wave modes, driven by render time
+#[derive(Debug, Clone, Copy)]
+pub struct ModeTransition {
+    pub from_mode: WaveMode,
+    pub to_mode: WaveMode,
+    /// Render time (seconds) at which progress was 0.0
+    pub start_time: f32,
+    pub duration: f32,
+}
+
+impl ModeTransition {
+    /// Transition progress (0.0 - 1.0) at the given render time
+    pub fn progress(&self, time: f32) -> f32 {
+        if self.duration <= 0.0 {
+            return 1.0;
+        }
+        ((time - self.start_time) / self.duration).clamp(0.0, 1.0)
+    }
+
+    /// Start a new transition toward `mode`, continuing from the blend visible at `time`
+    ///
+    /// Heading back to `from_mode` reverses in place; any other target starts
+    /// from whichever mode currently dominates the blend.
+    pub fn retarget(&self, mode: WaveMode, time: f32, duration: f32) -> ModeTransition {
+        let progress = self.progress(time);
+        let (from_mode, progress) = if mode == self.to_mode {
+            (self.from_mode, progress)
+        } else if mode == self.from_mode {
+            (self.to_mode, 1.0 - progress)
+        } else if progress >= 0.5 {
+            (self.to_mode, 0.0)
+        } else {
+            (self.from_mode, 0.0)
+        };
+
+        ModeTransition {
+            from_mode,
+            to_mode: mode,
+            start_time: time - progress * duration,
+            duration,
+        }
+    }
+}
+
+/// Curve shaping mode transition progress into the blend mix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Easing {
+    Linear,
+    /// Cubic, slow at both ends
+    EaseInOut,
+    /// Cubic, fast start settling into the new mode
+    EaseOut,
+    /// Hermite `3t² - 2t³`
+    Smoothstep,
+}
+
+impl Easing {
+    /// Map 0 linear, 1 ease-in-out, 2 ease-out, 3 smoothstep; anything else is linear
+    pub fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Easing::EaseInOut,
+            2 => Easing::EaseOut,
+            3 => Easing::Smoothstep,
+            _ => Easing::Linear,
+        }
+    }
+
+    /// Eased value of `t` (0.0 - 1.0); 0 and 1 are fixed points
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::EaseInOut => 1.0 - (2.0 - 2.0 * t).powi(3) * 0.5,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::Smoothstep => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Maximum number of SineWaves layers
+pub const MAX_LAYERS: u32 = 8;
+
+/// Default number of SineWaves layers, reproducing the original three-component wave
+/// here and the original harmonics in the shader
+pub const DEFAULT_LAYERS: u32 = 3;
+
+/// SineWaves layers as (x weight, y weight, frequency scale, speed, amplitude)
+///
+/// The shader keeps its own harmonic table (`sine_waves` in wave.wgsl); both match
+/// their original output at `DEFAULT_LAYERS`.
+const SINE_LAYERS: [(f32, f32, f32, f32, f32); MAX_LAYERS as usize] = [
+    (1.0, 0.0, 1.0, 1.0, 1.0),
+    (0.0, 1.0, 0.7, 1.3, 0.5),
+    (1.0, 1.0, 0.5, 0.7, 0.3),
+    (1.0, -1.0, 1.7, 1.9, 0.2),
+    (1.0, 0.0, 2.3, -1.1, 0.15),
+    (0.0, 1.0, 2.9, 0.9, 0.1),
+    (1.0, 1.0, 3.7, -1.6, 0.07),
+    (1.0, -1.0, 4.3, 2.1, 0.05),
+];
+
 /// Calculate wave displacement at a point
 /// This is used for generating wave mesh vertices
 pub fn calculate_wave(x: f32, y: f32, time: f32, params: &WaveParams) -> f32 {
-    let t = time * params.speed;
+    let t = time * params.speed + params.phase;
     
     match params.mode {
-        WaveMode::SineWaves => {
+        WaveMode::SineWaves | WaveMode::Particles => {
             // Multiple layered sine waves
-            let wave1 = (x * params.frequency + t).sin();
-            let wave2 = (y * params.frequency * 0.7 + t * 1.3).sin() * 0.5;
-            let wave3 = ((x + y) * params.frequency * 0.5 + t * 0.7).sin() * 0.3;
-            (wave1 + wave2 + wave3) * params.amplitude
+            let wave: f32 = SINE_LAYERS
+                .iter()
+                .take(params.layers.clamp(1, MAX_LAYERS) as usize)
+                .map(|&(dx, dy, freq, speed, weight)| {
+                    ((x * dx + y * dy) * params.frequency * freq + t * speed).sin() * weight
+                })
+                .sum();
+            wave * params.amplitude
         }
         WaveMode::CircularRipples => {
-            // Circular waves emanating from center
+            // Circular waves emanating from the pointer (center by default)
+            let x = x - (params.mouse[0] * 2.0 - 1.0);
+            let y = y - (params.mouse[1] * 2.0 - 1.0);
             let dist = (x * x + y * y).sqrt();
             (dist * params.frequency - t * 2.0).sin() * params.amplitude * (-dist * 0.5).exp()
         }
@@ -113,5 +388,324 @@ pub fn calculate_wave(x: f32, y: f32, time: f32, params: &WaveParams) -> f32 {
             let wave2 = ((x - y) * params.frequency * 0.5 + t * 0.7).sin() * 0.5;
             (wave1 + wave2) * params.amplitude
         }
+        WaveMode::Tunnel => {
+            // Log-mapped rings scrolling toward the viewer
+            let radius = (x * x + y * y).sqrt().max(0.001);
+            let depth = -radius.ln();
+            let rings = (depth * params.frequency * 4.0 + t * 4.0).sin();
+            let twist = (y.atan2(x) * 6.0 + depth * 2.0 + t).sin() * 0.3;
+            // Darken the vanishing point
+            let f = (radius / 0.3).min(1.0);
+            let fade = f * f * (3.0 - 2.0 * f);
+            (rings + twist) * params.amplitude * fade
+        }
+        // Drawn from the spectrum history or the stereo samples; there is no height field
+        WaveMode::Spectrogram | WaveMode::Bars3D | WaveMode::Goniometer => 0.0,
+    }
+}
+
+/// Evaluate the wave at a single point (CPU-side, for previews and fallbacks)
+#[wasm_bindgen]
+pub fn evaluate_wave(x: f32, y: f32, time: f32, params: &WaveParams) -> f32 {
+    calculate_wave(x, y, time, params)
+}
+
+/// Evaluate `width` samples along a scanline, with x spanning -1.0 to 1.0
+#[wasm_bindgen]
+pub fn evaluate_wave_row(y: f32, time: f32, width: usize, params: &WaveParams) -> Vec<f32> {
+    let step = if width > 1 { 2.0 / (width - 1) as f32 } else { 0.0 };
+    (0..width)
+        .map(|i| calculate_wave(-1.0 + i as f32 * step, y, time, params))
+        .collect()
+}
+
+/// Background color behind the trace, as in the shader
+const BACKGROUND: [f32; 3] = [0.02, 0.02, 0.05];
+
+/// Outer glow tint, as in the shader
+const HAZE: [f32; 3] = [0.1, 0.05, 0.15];
+
+/// Render the wave into a `width x height` RGBA8 buffer for CPU thumbnails
+///
+/// Mirrors the shader's glow line, coloring and tone mapping without post effects.
+/// Rows run top to bottom, ready for `ImageData` / `putImageData`.
+#[wasm_bindgen]
+pub fn render_to_buffer(width: usize, height: usize, time: f32, params: &WaveParams) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    let aspect = width as f32 / height.max(1) as f32;
+    let thickness = params.line_width.max(0.001);
+
+    for row in 0..height {
+        let y = 1.0 - (row as f32 + 0.5) / height as f32 * 2.0;
+        for col in 0..width {
+            let x = ((col as f32 + 0.5) / width as f32 * 2.0 - 1.0) * aspect;
+            let wave = calculate_wave(x, y, time, params);
+
+            let glow = |width: f32| {
+                let dist = (y - wave * 0.5) / (thickness * width);
+                (-dist * dist).exp()
+            };
+            let glow1 = glow(1.0);
+            let glow2 = glow(3.0) * 0.5;
+            let glow3 = glow(8.0) * 0.2;
+
+            let hue = wrap_hue(params.hue + wave * 30.0 + x * 20.0);
+            let primary = hsl_to_rgb(hue, params.saturation, (params.lightness + glow1 * 0.3).min(1.0));
+            let secondary = hsl_to_rgb(
+                wrap_hue(hue + params.hue2),
+                (params.saturation - 0.1).max(0.0),
+                (params.lightness - 0.1).max(0.0),
+            );
+
+            for channel in 0..3 {
+                let color = primary[channel] * glow1
+                    + secondary[channel] * glow2 * 0.5
+                    + HAZE[channel] * glow3
+                    + BACKGROUND[channel];
+                // Reinhard tone mapping and gamma, like the shader's tone_map
+                let exposed = color * params.exposure;
+                let mapped = (exposed / (exposed + 1.0)).powf(1.0 / params.gamma);
+                pixels.push((mapped.clamp(0.0, 1.0) * 255.0).round() as u8);
+            }
+            pixels.push(255);
+        }
+    }
+    pixels
+}
+
+/// Sample the wave over a `resolution x resolution` grid spanning -1.0 to 1.0
+///
+/// Returns interleaved (x, height, z) positions, row by row along z.
+pub fn wave_mesh(resolution: u32, time: f32, params: &WaveParams) -> Vec<f32> {
+    let resolution = resolution.max(2) as usize;
+    let mut positions = Vec::with_capacity(resolution * resolution * 3);
+    for row in 0..resolution {
+        let z = -1.0 + row as f32 * 2.0 / (resolution - 1) as f32;
+        for (col, height) in evaluate_wave_row(z, time, resolution, params).into_iter().enumerate() {
+            let x = -1.0 + col as f32 * 2.0 / (resolution - 1) as f32;
+            positions.extend_from_slice(&[x, height, z]);
+        }
+    }
+    positions
+}
+
+/// Triangle indices for a mesh from `wave_mesh` with the same resolution
+#[wasm_bindgen]
+pub fn generate_mesh_indices(resolution: u32) -> Vec<u32> {
+    let resolution = resolution.max(2);
+    let mut indices = Vec::with_capacity(((resolution - 1) * (resolution - 1) * 6) as usize);
+    for row in 0..resolution - 1 {
+        for col in 0..resolution - 1 {
+            let top_left = row * resolution + col;
+            let bottom_left = top_left + resolution;
+            indices.extend_from_slice(&[
+                top_left,
+                bottom_left,
+                top_left + 1,
+                top_left + 1,
+                bottom_left,
+                bottom_left + 1,
+            ]);
+        }
+    }
+    indices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f32 = 1e-4;
+
+    /// Reference points as (x, y, time)
+    const POINTS: [(f32, f32, f32); 3] = [(0.0, 0.0, 0.0), (0.5, -0.25, 1.0), (-0.75, 0.5, 2.5)];
+
+    fn params(mode: WaveMode) -> WaveParams {
+        WaveParams {
+            mode,
+            target_mode: mode,
+            ..WaveParams::default()
+        }
+    }
+
+    /// Evaluate `mode` on a grid over -1.0..1.0 and several times
+    fn grid_samples(mode: WaveMode) -> Vec<f32> {
+        let params = params(mode);
+        let mut samples = Vec::new();
+        for i in 0..=20 {
+            for j in 0..=20 {
+                for &time in &[0.0, 0.37, 1.0, 4.2, 100.0] {
+                    let x = -1.0 + i as f32 * 0.1;
+                    let y = -1.0 + j as f32 * 0.1;
+                    samples.push(calculate_wave(x, y, time, &params));
+                }
+            }
+        }
+        samples
+    }
+
+    fn assert_golden(mode: WaveMode, expected: [f32; 3]) {
+        let params = params(mode);
+        for (&(x, y, time), expected) in POINTS.iter().zip(expected) {
+            let actual = calculate_wave(x, y, time, &params);
+            assert!(
+                (actual - expected).abs() < EPSILON,
+                "{:?} at ({}, {}, t={}): expected {}, got {}",
+                mode, x, y, time, expected, actual
+            );
+        }
+    }
+
+    fn assert_bounded(mode: WaveMode, bound: f32) {
+        let amplitude = params(mode).amplitude;
+        for sample in grid_samples(mode) {
+            assert!(sample.is_finite(), "{:?} produced {}", mode, sample);
+            assert!(sample.abs() <= amplitude * bound + EPSILON, "{:?} produced {}", mode, sample);
+        }
+    }
+
+    #[test]
+    fn sine_waves_golden() {
+        assert_golden(WaveMode::SineWaves, [0.0, 1.212207, 0.083589]);
+    }
+
+    #[test]
+    fn default_sine_waves_match_original_wave() {
+        // The three components calculate_wave summed before layers were configurable
+        let original = |x: f32, y: f32, t: f32| {
+            let f = 3.0;
+            (x * f + t).sin() + (y * f * 0.7 + t * 1.3).sin() * 0.5 + ((x + y) * f * 0.5 + t * 0.7).sin() * 0.3
+        };
+        let params = params(WaveMode::SineWaves);
+        for i in 0..=20 {
+            for j in 0..=20 {
+                let (x, y) = (-1.0 + i as f32 * 0.1, -1.0 + j as f32 * 0.1);
+                for &time in &[0.0, 0.37, 1.0, 4.2] {
+                    assert_eq!(calculate_wave(x, y, time, &params), original(x, y, time));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shader_default_layers_keep_original_harmonics() {
+        // The shader drew 3x, 5x, 7x and 11x harmonics plus a y variation: the first
+        // three are its default layers, added before the fixed 11x and y terms
+        let shader = include_str!("../shaders/wave.wgsl");
+        let harmonics = shader.find("var harmonics").expect("sine_waves harmonic table");
+        for (index, layer) in ["(3.0, 2.0, 0.4)", "(5.0, -1.5, 0.3)", "(7.0, 2.5, 0.2)"].iter().enumerate() {
+            let entry = shader[harmonics..].find(layer).expect("original harmonic");
+            let before = &shader[harmonics..harmonics + entry];
+            assert_eq!(before.matches("vec3<f32>(").count(), index, "{} out of order", layer);
+        }
+        let fixed = shader.find("wave += sin(uv.x * freq * 11.0 - t * 3.0) * 0.1;").expect("11x harmonic");
+        let variation = shader.find("wave += sin(uv.y * freq * 2.0 + t) * 0.15;").expect("y variation");
+        assert!(harmonics < fixed && fixed < variation);
+        assert_eq!(DEFAULT_LAYERS, 3);
+    }
+
+    #[test]
+    fn circular_ripples_golden() {
+        assert_golden(WaveMode::CircularRipples, [0.0, -0.239977, -0.476916]);
+    }
+
+    #[test]
+    fn lissajous_curves_golden() {
+        assert_golden(WaveMode::LissajousCurves, [0.0, 0.0, 0.402785]);
+    }
+
+    #[test]
+    fn plasma_field_golden() {
+        assert_golden(WaveMode::PlasmaField, [0.0, 0.698559, -0.044404]);
+    }
+
+    #[test]
+    fn wave_surface_golden() {
+        assert_golden(WaveMode::WaveSurface, [0.841471, 0.884749, 0.913151]);
+    }
+
+    #[test]
+    fn tunnel_golden() {
+        assert_golden(WaveMode::Tunnel, [0.000041, -1.173863, -1.188268]);
+    }
+
+    #[test]
+    fn particles_match_sine_waves() {
+        assert_eq!(grid_samples(WaveMode::Particles), grid_samples(WaveMode::SineWaves));
+    }
+
+    #[test]
+    fn modes_stay_within_amplitude_bounds() {
+        // Sum of the layer weights (three layers by default)
+        assert_bounded(WaveMode::SineWaves, 1.8);
+        assert_bounded(WaveMode::CircularRipples, 1.0);
+        assert_bounded(WaveMode::LissajousCurves, 1.0);
+        assert_bounded(WaveMode::PlasmaField, 1.0);
+        assert_bounded(WaveMode::WaveSurface, 1.5);
+        assert_bounded(WaveMode::Tunnel, 1.3);
+    }
+
+    #[test]
+    fn circular_ripples_decay() {
+        let params = params(WaveMode::CircularRipples);
+
+        // No decay at the origin: sin(-2t) peaks at full amplitude for t = pi / 4
+        let time = std::f32::consts::FRAC_PI_4;
+        let origin = calculate_wave(0.0, 0.0, time, &params);
+        assert!((origin + params.amplitude).abs() < EPSILON, "origin: {}", origin);
+
+        // Far out the envelope exp(-dist / 2) dominates
+        for &dist in &[4.0f32, 10.0, 20.0] {
+            let envelope = (-dist * 0.5).exp() * params.amplitude;
+            for step in 0..16 {
+                let value = calculate_wave(dist, 0.0, step as f32 * 0.1, &params);
+                assert!(value.abs() <= envelope + EPSILON, "dist {}: {}", dist, value);
+            }
+        }
+    }
+
+    #[test]
+    fn easing_curves_are_monotonic_with_fixed_ends() {
+        for easing in (0..4).map(Easing::from_u32) {
+            assert!(easing.apply(0.0).abs() < EPSILON, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < EPSILON, "{:?}", easing);
+            let samples: Vec<f32> = (0..=100).map(|i| easing.apply(i as f32 / 100.0)).collect();
+            assert!(samples.windows(2).all(|pair| pair[1] >= pair[0]), "{:?}", easing);
+        }
+        assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < EPSILON);
+        assert!((Easing::Smoothstep.apply(0.5) - 0.5).abs() < EPSILON);
+        assert!(Easing::EaseOut.apply(0.25) > 0.25);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trips_params() {
+        assert_eq!(serde_json::to_string(&WaveMode::Tunnel).unwrap(), "6");
+        assert_eq!(serde_json::from_str::<WaveMode>("8").unwrap(), WaveMode::Bars3D);
+
+        let mut params = params(WaveMode::Goniometer);
+        params.pulse = Some(0.5);
+        let json = serde_json::to_string(&params).unwrap();
+        assert!(!json.contains("pulse"));
+        let restored: WaveParams = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.mode, WaveMode::Goniometer);
+        assert_eq!(restored.pulse, None);
+
+        // Partial presets fill the rest from the defaults
+        let partial: WaveParams = serde_json::from_str(r#"{"mode": 3, "hue": 90.0}"#).unwrap();
+        assert_eq!(partial.mode, WaveMode::PlasmaField);
+        assert_eq!(partial.layers, WaveParams::default().layers);
+    }
+
+    #[test]
+    fn render_to_buffer_is_opaque_rgba() {
+        let params = params(WaveMode::SineWaves);
+        let pixels = render_to_buffer(16, 9, 1.0, &params);
+        assert_eq!(pixels.len(), 16 * 9 * 4);
+        assert!(pixels.chunks(4).all(|pixel| pixel[3] == 255));
+        // The trace is brighter than the background somewhere
+        let brightest = pixels.chunks(4).map(|pixel| pixel[0].max(pixel[1]).max(pixel[2])).max();
+        let corner = pixels[0].max(pixels[1]).max(pixels[2]);
+        assert!(brightest > Some(corner));
     }
 }
@@ -10,11 +10,49 @@ struct Uniforms {
     resolution: vec2<f32>,
     hue: f32,
     mode: u32,
+    target_mode: u32,
+    mix: f32,
+    symmetry: u32,
+    polar: u32,
+    bands: array<vec4<f32>, 2>,
+    line_width: f32,
+    exposure: f32,
+    gamma: f32,
+    grid: u32,
+    hue2: f32,
+    saturation: f32,
+    lightness: f32,
+    camera_offset: vec2<f32>,
+    persistence: f32,
+    color_by_frequency: u32,
+    layers: u32,
+    aberration: f32,
+    mouse: vec2<f32>,
+    intensity_from_amplitude: f32,
+    spectrogram_cursor: f32,
+    phase: f32,
+    transparent: u32,
+    pulse_phase: f32,
+    pulse_enabled: u32,
+    highlight_band: i32,
+    bar_columns: u32,
+    background: vec4<f32>,
 }
 
 @group(0) @binding(0)
 var<uniform> uniforms: Uniforms;
 
+// Spectrum history ring buffer: x = column (time), y = band (bass first)
+@group(0) @binding(1)
+var spectrogram_texture: texture_2d<f32>;
+@group(0) @binding(2)
+var spectrogram_sampler: sampler;
+
+const PARTICLES_MODE: u32 = 5u;
+const SPECTROGRAM_MODE: u32 = 7u;
+const BARS_MODE: u32 = 8u;
+const GONIOMETER_MODE: u32 = 9u;
+
 // Vertex input/output
 struct VertexInput {
     @location(0) position: vec3<f32>,
@@ -40,7 +78,7 @@ fn vs_main(in: VertexInput) -> VertexOutput {
 
 // ==================== UTILITY FUNCTIONS ====================
 
-// HSL to RGB conversion
+// HSL to RGB conversion; mirrored on the CPU by palette::hsl_to_rgb, keep them in sync
 fn hsl2rgb(h: f32, s: f32, l: f32) -> vec3<f32> {
     let c = (1.0 - abs(2.0 * l - 1.0)) * s;
     let hp = h / 60.0;
@@ -99,6 +137,27 @@ fn fbm(p: vec2<f32>) -> f32 {
     return value;
 }
 
+// Fold coordinates for mirror symmetry (0 none, 1 horizontal, 2 vertical, 3 quad)
+fn fold_symmetry(uv: vec2<f32>, symmetry: u32) -> vec2<f32> {
+    var folded = uv;
+    if (symmetry == 1u || symmetry == 3u) {
+        folded.x = abs(folded.x);
+    }
+    if (symmetry == 2u || symmetry == 3u) {
+        folded.y = abs(folded.y);
+    }
+    return folded;
+}
+
+// Wrap coordinates into polar space: x follows the angle, y the radius
+fn to_polar(uv: vec2<f32>) -> vec2<f32> {
+    // Clamp radius to avoid the singularity at the center
+    let radius = max(length(uv), 0.02);
+    let angle = atan2(uv.y, uv.x);
+    let aspect = uniforms.resolution.x / uniforms.resolution.y;
+    return vec2<f32>(angle / 3.14159265 * aspect, radius * 2.0 - 1.0);
+}
+
 // ==================== WAVE MODES ====================
 
 // Mode 0: Sine Waves
@@ -106,10 +165,25 @@ fn sine_waves(uv: vec2<f32>, t: f32) -> f32 {
     let freq = uniforms.frequency;
     let amp = uniforms.amplitude;
     
-    // Multiple overlapping sine waves
-    var wave = sin(uv.x * freq * 3.0 + t * 2.0) * 0.4;
-    wave += sin(uv.x * freq * 5.0 - t * 1.5) * 0.3;
-    wave += sin(uv.x * freq * 7.0 + t * 2.5) * 0.2;
+    // Multiple overlapping sine waves, one per layer with falling weight, as
+    // (frequency scale, speed, amplitude); the default three layers plus the fixed
+    // 11x harmonic below are the original four
+    var harmonics = array<vec3<f32>, 8>(
+        vec3<f32>(3.0, 2.0, 0.4),
+        vec3<f32>(5.0, -1.5, 0.3),
+        vec3<f32>(7.0, 2.5, 0.2),
+        vec3<f32>(13.0, 3.5, 0.07),
+        vec3<f32>(17.0, -4.0, 0.05),
+        vec3<f32>(19.0, 4.5, 0.035),
+        vec3<f32>(23.0, -5.0, 0.025),
+        vec3<f32>(29.0, 5.5, 0.018),
+    );
+    var wave = 0.0;
+    let layers = clamp(uniforms.layers, 1u, 8u);
+    for (var i = 0u; i < layers; i++) {
+        let h = harmonics[i];
+        wave += sin(uv.x * freq * h.x + t * h.y) * h.z;
+    }
     wave += sin(uv.x * freq * 11.0 - t * 3.0) * 0.1;
     
     // Add subtle horizontal variation
@@ -118,11 +192,19 @@ fn sine_waves(uv: vec2<f32>, t: f32) -> f32 {
     return wave * amp;
 }
 
+// Pointer position in world coordinates (origin when centered)
+fn mouse_origin() -> vec2<f32> {
+    return (uniforms.mouse * 2.0 - 1.0) * vec2<f32>(uniforms.resolution.x / uniforms.resolution.y, 1.0);
+}
+
 // Mode 1: Circular Ripples
-fn circular_ripples(uv: vec2<f32>, t: f32) -> f32 {
+fn circular_ripples(pos: vec2<f32>, t: f32) -> f32 {
     let freq = uniforms.frequency;
     let amp = uniforms.amplitude;
     
+    // Emanate from the pointer
+    let uv = pos - mouse_origin();
+    
     let dist = length(uv);
     let wave = sin(dist * freq * 10.0 - t * 3.0);
     let fade = exp(-dist * 0.5);
@@ -201,6 +283,90 @@ fn wave_surface(uv: vec2<f32>, t: f32) -> f32 {
     return wave * amp * perspective;
 }
 
+// Mode 6: Tunnel (infinite zoom)
+fn tunnel(uv: vec2<f32>, t: f32) -> f32 {
+    let freq = uniforms.frequency;
+    let amp = uniforms.amplitude;
+    
+    // Log-mapped radius makes evenly scrolling rings read as endless depth
+    let radius = max(length(uv), 0.001);
+    let depth = -log(radius);
+    let rings = sin(depth * freq * 4.0 + t * 4.0);
+    let twist = sin(atan2(uv.y, uv.x) * 6.0 + depth * 2.0 + t) * 0.3;
+    
+    // Darken the vanishing point
+    let fade = smoothstep(0.0, 0.3, radius);
+    
+    return (rings + twist) * amp * fade;
+}
+
+// Dispatch to the wave function for a mode
+fn evaluate_mode(mode: u32, uv: vec2<f32>, t: f32) -> f32 {
+    switch mode {
+        case 0u: {
+            return sine_waves(uv, t);
+        }
+        case 1u: {
+            return circular_ripples(uv, t);
+        }
+        case 2u: {
+            return lissajous_curves(uv, t);
+        }
+        case 3u: {
+            return plasma_field(uv, t);
+        }
+        case 4u: {
+            return wave_surface(uv, t);
+        }
+        case 6u: {
+            return tunnel(uv, t);
+        }
+        default: {
+            // No trace: drawn by spectrogram_color or by the mode's own pass
+            return 0.0;
+        }
+    }
+}
+
+// Whether a mode is drawn as a wave trace by the fullscreen scene
+fn has_trace(mode: u32) -> bool {
+    return mode <= 4u || mode == 6u;
+}
+
+// Share of the cross-fade held by `mode` (1.0 outside transitions when it is the mode)
+fn mode_weight(mode: u32) -> f32 {
+    var weight = 0.0;
+    if (uniforms.mode == mode) {
+        weight = 1.0 - uniforms.mix;
+    }
+    if (uniforms.target_mode == mode) {
+        weight += uniforms.mix;
+    }
+    return weight;
+}
+
+// Mode 7: Spectrogram (scrolling waterfall), newest column at the right edge
+fn spectrogram_color(screen_uv: vec2<f32>) -> vec4<f32> {
+    // Offset by the write cursor so the oldest column sits at the left edge
+    let coord = vec2<f32>(uniforms.spectrogram_cursor + screen_uv.x, 1.0 - screen_uv.y);
+    let level = textureSampleLevel(spectrogram_texture, spectrogram_sampler, coord, 0.0).r;
+    
+    // Quiet bins stay at the base hue, loud bins swing toward hue2
+    let hue = (uniforms.hue + uniforms.hue2 * level) % 360.0;
+    let color = hsl2rgb(hue, uniforms.saturation, uniforms.lightness * level) * level * 2.0;
+    return vec4<f32>(color, level);
+}
+
+// Level of frequency band `i` (0-7)
+fn band_level(i: u32) -> f32 {
+    return uniforms.bands[i / 4u][i % 4u];
+}
+
+// Hue for a normalized frequency position: bass (0.0) red → treble (1.0) blue
+fn frequency_hue(position: f32) -> f32 {
+    return clamp(position, 0.0, 1.0) * 240.0;
+}
+
 // ==================== GLOW EFFECTS ====================
 
 fn glow_line(uv: vec2<f32>, wave_height: f32, thickness: f32) -> f32 {
@@ -213,39 +379,65 @@ fn bloom(intensity: f32, power: f32) -> f32 {
     return pow(max(intensity, 0.0), power);
 }
 
+// Antialiased grid line coverage with `divisions` cells across the height
+fn graticule_lines(uv: vec2<f32>, divisions: f32) -> f32 {
+    let coord = uv * divisions * 0.5;
+    let width = fwidth(coord);
+    let dist = abs(fract(coord - 0.5) - 0.5) / width;
+    return 1.0 - min(min(dist.x, dist.y), 1.0);
+}
+
+// Exposure, Reinhard tone mapping and gamma correction
+fn tone_map(color: vec3<f32>) -> vec3<f32> {
+    var mapped = color * uniforms.exposure;
+    mapped = mapped / (mapped + 1.0);
+    return pow(mapped, vec3<f32>(1.0 / uniforms.gamma));
+}
+
 // ==================== FRAGMENT SHADER ====================
 
-@fragment
-fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    let uv = in.world_pos;
-    let t = uniforms.time * uniforms.speed;
+// Wave coordinates for a world position, after camera, symmetry and polar mapping
+fn scene_uv(world_pos: vec2<f32>) -> vec2<f32> {
+    var uv = fold_symmetry(world_pos + uniforms.camera_offset, uniforms.symmetry);
+    if (uniforms.polar != 0u) {
+        uv = to_polar(uv);
+    }
+    return uv;
+}
+
+// Glowing wave color at a world position; total glow in alpha
+fn wave_color(world_pos: vec2<f32>, screen_uv: vec2<f32>) -> vec4<f32> {
+    // The spectrogram is an image rather than a trace, so it cross-fades as a whole
+    let spectrogram_mix = mode_weight(SPECTROGRAM_MODE);
+    // Modes without a trace fade the trace out instead of flattening it
+    let from_trace = has_trace(uniforms.mode);
+    let to_trace = uniforms.mix > 0.0 && has_trace(uniforms.target_mode);
+    var trace_weight = 0.0;
+    if (from_trace) {
+        trace_weight = 1.0 - uniforms.mix;
+    }
+    if (to_trace) {
+        trace_weight += uniforms.mix;
+    }
+    if (trace_weight <= 0.0) {
+        return spectrogram_color(screen_uv) * spectrogram_mix;
+    }
     
-    var wave: f32;
+    let uv = scene_uv(world_pos);
+    let t = uniforms.time * uniforms.speed + uniforms.phase;
     
-    // Calculate wave based on mode
-    switch uniforms.mode {
-        case 0u: {
-            wave = sine_waves(uv, t);
-        }
-        case 1u: {
-            wave = circular_ripples(uv, t);
-        }
-        case 2u: {
-            wave = lissajous_curves(uv, t);
-        }
-        case 3u: {
-            wave = plasma_field(uv, t);
-        }
-        case 4u: {
-            wave = wave_surface(uv, t);
-        }
-        default: {
-            wave = sine_waves(uv, t);
-        }
+    // Calculate wave based on mode, cross-fading between traces during transitions
+    var wave = 0.0;
+    if (from_trace && to_trace) {
+        wave = mix(evaluate_mode(uniforms.mode, uv, t), evaluate_mode(uniforms.target_mode, uv, t), uniforms.mix);
+    } else if (from_trace) {
+        wave = evaluate_mode(uniforms.mode, uv, t);
+    } else {
+        wave = evaluate_mode(uniforms.target_mode, uv, t);
     }
     
     // Create glowing line effect
-    let line_thickness = 0.08;
+    let line_thickness = uniforms.line_width;
     let glow1 = glow_line(uv, wave * 0.5, line_thickness);
     let glow2 = glow_line(uv, wave * 0.5, line_thickness * 3.0) * 0.5;
     let glow3 = glow_line(uv, wave * 0.5, line_thickness * 8.0) * 0.2;
@@ -253,15 +445,18 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let total_glow = glow1 + glow2 + glow3;
     
     // Dynamic color based on wave position and time
-    let hue_shift = uniforms.hue + wave * 30.0 + uv.x * 20.0;
+    var hue_shift = uniforms.hue + wave * 30.0 + uv.x * 20.0;
+    if (uniforms.color_by_frequency != 0u) {
+        hue_shift = frequency_hue(screen_uv.x);
+    }
     let hue = (hue_shift % 360.0 + 360.0) % 360.0;
     
     // Primary color (cyan-magenta gradient)
-    let primary_color = hsl2rgb(hue, 0.9, 0.5 + glow1 * 0.3);
+    let primary_color = hsl2rgb(hue, uniforms.saturation, uniforms.lightness + glow1 * 0.3);
     
     // Secondary glow color (complementary)
-    let secondary_hue = (hue + 180.0) % 360.0;
-    let secondary_color = hsl2rgb(secondary_hue, 0.8, 0.4);
+    let secondary_hue = (hue + uniforms.hue2) % 360.0;
+    let secondary_color = hsl2rgb(secondary_hue, max(uniforms.saturation - 0.1, 0.0), max(uniforms.lightness - 0.1, 0.0));
     
     // Mix colors based on glow intensity
     var color = primary_color * glow1;
@@ -272,25 +467,290 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
     let bloom_amount = bloom(total_glow, 2.0) * 0.3;
     color += primary_color * bloom_amount;
     
+    // Flare toward white where the wave swings hardest
+    let flare = clamp(abs(wave) * uniforms.intensity_from_amplitude, 0.0, 1.0);
+    color = mix(color, vec3<f32>(total_glow), flare * 0.8);
+    
+    let trace = vec4<f32>(color, total_glow) * trace_weight;
+    if (spectrogram_mix > 0.0) {
+        return trace + spectrogram_color(screen_uv) * spectrogram_mix;
+    }
+    return trace;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let uv = scene_uv(in.world_pos);
+    
+    let glow = wave_color(in.world_pos, in.uv);
+    var color = glow.rgb;
+    let total_glow = glow.a;
+    
+    // Chromatic aberration: red and blue from horizontally offset positions
+    if (uniforms.aberration > 0.0) {
+        let offset = vec2<f32>(uniforms.aberration, 0.0);
+        let screen_offset = vec2<f32>(offset.x * 0.5, 0.0);
+        color.r = wave_color(in.world_pos + offset, in.uv + screen_offset).r;
+        color.b = wave_color(in.world_pos - offset, in.uv - screen_offset).b;
+    }
+    
     // Background gradient
     let bg_gradient = 0.02 + uv.y * 0.01;
-    let bg_color = vec3<f32>(0.02, 0.02, 0.05) + vec3<f32>(0.01, 0.0, 0.02) * (1.0 - abs(uv.y));
+    let bg_color = uniforms.background.rgb + vec3<f32>(0.01, 0.0, 0.02) * (1.0 - abs(uv.y));
     
     // Add subtle grid pattern
     let grid = sin(uv.x * 50.0) * sin(uv.y * 50.0);
     let grid_intensity = (grid * 0.5 + 0.5) * 0.02;
     
-    // Final composition
-    var final_color = color + bg_color;
-    final_color += vec3<f32>(0.0, 0.02, 0.03) * grid_intensity;
+    // Graticule overlay, drawn under the wave
+    var graticule = 0.0;
+    if (uniforms.grid > 0u) {
+        graticule = graticule_lines(in.world_pos, f32(uniforms.grid)) * (1.0 - min(total_glow, 1.0));
+    }
+    
+    // Final composition; a transparent canvas drops the background so the page shows through.
+    // A picked background (w = 1) is already in output space and goes on after tone mapping.
+    let picked_background = uniforms.background.w > 0.5;
+    var final_color = color;
+    if (uniforms.transparent == 0u && !picked_background) {
+        final_color += bg_color;
+        final_color += vec3<f32>(0.0, 0.02, 0.03) * grid_intensity;
+    }
+    final_color += hsl2rgb(uniforms.hue, 0.6, 0.3) * graticule * 0.35;
     
     // Add vignette
     let vignette = 1.0 - length(in.uv - 0.5) * 0.5;
     final_color *= vignette;
     
     // Tone mapping and gamma correction
-    final_color = final_color / (final_color + 1.0); // Reinhard tone mapping
-    final_color = pow(final_color, vec3<f32>(1.0 / 2.2)); // Gamma correction
+    // Tempo pulse: flash on each beat, decaying until the next
+    if (uniforms.pulse_enabled != 0u) {
+        final_color *= 1.0 + pow(1.0 - uniforms.pulse_phase, 4.0) * 0.5;
+    }
     
+    final_color = tone_map(final_color);
+    
+    if (uniforms.transparent != 0u) {
+        // Premultiplied output: coverage follows the brightest channel
+        return vec4<f32>(final_color, max(final_color.r, max(final_color.g, final_color.b)));
+    }
+    if (picked_background) {
+        // Screen the scene over the picked color, which shows unchanged where nothing is drawn
+        final_color = 1.0 - (1.0 - uniforms.background.rgb) * (1.0 - final_color);
+    }
     return vec4<f32>(final_color, 1.0);
 }
+
+// ==================== PARTICLES ====================
+
+struct ParticleInput {
+    @location(0) position: vec2<f32>,
+    @location(1) band: f32,
+    @location(2) seed: f32,
+}
+
+struct ParticleOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) corner: vec2<f32>,
+    @location(1) level: f32,
+    @location(2) seed: f32,
+    @location(3) band: f32,
+}
+
+// Particle vertex shader - one small quad per instance, displaced by the wave
+@vertex
+fn vs_particles(@builtin(vertex_index) vertex_index: u32, particle: ParticleInput) -> ParticleOutput {
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-1.0, -1.0),
+        vec2<f32>(1.0, -1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(1.0, 1.0),
+        vec2<f32>(-1.0, 1.0),
+        vec2<f32>(-1.0, -1.0),
+    );
+    let corner = corners[vertex_index];
+
+    let aspect = uniforms.resolution.x / uniforms.resolution.y;
+    let t = uniforms.time * uniforms.speed + uniforms.phase;
+    let level = band_level(u32(particle.band));
+
+    // Displace by the wave function, scaled by the particle's band
+    let base = particle.position * vec2<f32>(aspect, 1.0);
+    let wave = sine_waves(base, t + particle.seed * 6.2831853);
+    var pos = base + vec2<f32>(0.0, wave * 0.3 * (0.5 + level)) - uniforms.camera_offset;
+
+    let size = 0.006 + level * 0.02;
+    pos += corner * size;
+
+    var out: ParticleOutput;
+    out.clip_position = vec4<f32>(pos.x / aspect, pos.y, 0.0, 1.0);
+    out.corner = corner;
+    out.level = level;
+    out.seed = particle.seed;
+    out.band = particle.band;
+    return out;
+}
+
+@fragment
+fn fs_particles(in: ParticleOutput) -> @location(0) vec4<f32> {
+    // Soft round sprite
+    let falloff = 1.0 - smoothstep(0.0, 1.0, length(in.corner));
+
+    var hue = (uniforms.hue + in.level * 90.0 + in.seed * 30.0) % 360.0;
+    if (uniforms.color_by_frequency != 0u) {
+        hue = frequency_hue(in.band / 7.0);
+    }
+    var color = hsl2rgb(hue, uniforms.saturation, uniforms.lightness + in.level * 0.3) * (0.4 + in.level);
+
+    // Tone mapping and gamma correction
+    color = tone_map(color);
+
+    return vec4<f32>(color, falloff * mode_weight(PARTICLES_MODE));
+}
+
+// ==================== BARS 3D ====================
+
+struct BarInput {
+    // Floor center (x, z) and footprint (width, depth)
+    @location(0) cell: vec4<f32>,
+    @location(1) height: f32,
+    // 0.0 (bass) - 1.0 (treble)
+    @location(2) column: f32,
+}
+
+struct BarOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) shade: f32,
+    @location(1) height: f32,
+    @location(2) column: f32,
+}
+
+// Isometric view: yaw 45 degrees, then pitch atan(1 / sqrt(2)) so all three axes
+// foreshorten equally. The front corner (-x, -z) faces the viewer.
+fn isometric(p: vec3<f32>) -> vec4<f32> {
+    let yawed = vec3<f32>((p.x - p.z) * 0.70710678, p.y, (p.x + p.z) * 0.70710678);
+    let cos_pitch = 0.81649658;
+    let sin_pitch = 0.57735027;
+    let screen_y = yawed.y * cos_pitch + yawed.z * sin_pitch;
+    let depth = yawed.z * cos_pitch - yawed.y * sin_pitch;
+    let aspect = uniforms.resolution.x / uniforms.resolution.y;
+    let scale = 0.55;
+    return vec4<f32>(yawed.x * scale / aspect, screen_y * scale - 0.35, depth * 0.25 + 0.55, 1.0);
+}
+
+// Bar vertex shader - one unit cube per instance, scaled to the bar's cell and height
+@vertex
+fn vs_bars(@builtin(vertex_index) vertex_index: u32, bar: BarInput) -> BarOutput {
+    var corners = array<vec3<f32>, 8>(
+        vec3<f32>(0.0, 0.0, 0.0),
+        vec3<f32>(1.0, 0.0, 0.0),
+        vec3<f32>(1.0, 1.0, 0.0),
+        vec3<f32>(0.0, 1.0, 0.0),
+        vec3<f32>(0.0, 0.0, 1.0),
+        vec3<f32>(1.0, 0.0, 1.0),
+        vec3<f32>(1.0, 1.0, 1.0),
+        vec3<f32>(0.0, 1.0, 1.0),
+    );
+    // Two triangles per face, faces ordered +x, -x, +y, -y, +z, -z
+    var indices = array<u32, 36>(
+        1u, 5u, 6u, 6u, 2u, 1u,
+        4u, 0u, 3u, 3u, 7u, 4u,
+        3u, 2u, 6u, 6u, 7u, 3u,
+        4u, 5u, 1u, 1u, 0u, 4u,
+        5u, 4u, 7u, 7u, 6u, 5u,
+        0u, 1u, 2u, 2u, 3u, 0u,
+    );
+    // Lit top, dimmer sides; the hidden faces never win the depth test
+    var face_shade = array<f32, 6>(0.5, 0.75, 1.0, 0.3, 0.5, 0.6);
+
+    let corner = corners[indices[vertex_index]];
+    let height = max(bar.height, 0.01) * 1.2;
+    let world = vec3<f32>(
+        bar.cell.x + (corner.x - 0.5) * bar.cell.z,
+        corner.y * height,
+        bar.cell.y + (corner.z - 0.5) * bar.cell.w,
+    );
+
+    var out: BarOutput;
+    out.clip_position = isometric(world);
+    out.shade = face_shade[vertex_index / 6u];
+    out.height = bar.height;
+    out.column = bar.column;
+    return out;
+}
+
+@fragment
+fn fs_bars(in: BarOutput) -> @location(0) vec4<f32> {
+    var hue = (uniforms.hue + in.column * 120.0) % 360.0;
+    if (uniforms.color_by_frequency != 0u) {
+        hue = frequency_hue(in.column);
+    }
+    let lightness = min(uniforms.lightness + in.height * 0.25, 1.0);
+    var color = hsl2rgb(hue, uniforms.saturation, lightness) * in.shade * (0.6 + in.height);
+
+    // Solo band: light up the highlighted column, dim the others
+    if (uniforms.highlight_band >= 0) {
+        let last_column = f32(max(uniforms.bar_columns, 1u) - 1u);
+        let band = i32(round(in.column * last_column));
+        if (band == uniforms.highlight_band) {
+            color = mix(color * 1.5, vec3<f32>(1.0), 0.25);
+        } else {
+            color *= 0.3;
+        }
+    }
+
+    return vec4<f32>(tone_map(color), mode_weight(BARS_MODE));
+}
+
+// ==================== GONIOMETER ====================
+
+struct GoniometerOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) radius: f32,
+}
+
+// Goniometer vertex shader - (side, mid) points in a square centered on screen
+@vertex
+fn vs_goniometer(@location(0) point: vec2<f32>) -> GoniometerOutput {
+    let aspect = uniforms.resolution.x / uniforms.resolution.y;
+    let pos = clamp(point * uniforms.amplitude * 0.7, vec2<f32>(-0.95), vec2<f32>(0.95)) - uniforms.camera_offset;
+
+    var out: GoniometerOutput;
+    out.clip_position = vec4<f32>(pos.x / max(aspect, 1.0), pos.y * min(aspect, 1.0), 0.0, 1.0);
+    out.radius = length(point);
+    return out;
+}
+
+@fragment
+fn fs_goniometer(in: GoniometerOutput) -> @location(0) vec4<f32> {
+    // Louder excursions shift hue and brighten
+    let level = clamp(in.radius, 0.0, 1.0);
+    let hue = (uniforms.hue + level * 60.0) % 360.0;
+    let color = hsl2rgb(hue, uniforms.saturation, uniforms.lightness + level * 0.2) * (0.8 + level);
+
+    return vec4<f32>(tone_map(color), 0.9 * mode_weight(GONIOMETER_MODE));
+}
+
+// ==================== PERSISTENCE ====================
+
+@group(1) @binding(0)
+var history_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var history_sampler: sampler;
+
+// Fade last frame toward the background, leaving phosphor trails
+@fragment
+fn fs_fade(in: VertexOutput) -> @location(0) vec4<f32> {
+    let previous = textureSample(history_texture, history_sampler, in.uv).rgb;
+    var background = uniforms.background.rgb;
+    if (uniforms.background.w < 0.5) {
+        background = tone_map(background);
+    }
+    return vec4<f32>(mix(background, previous, uniforms.persistence), 1.0);
+}
+
+// Copy the history texture to the output
+@fragment
+fn fs_blit(in: VertexOutput) -> @location(0) vec4<f32> {
+    return vec4<f32>(textureSample(history_texture, history_sampler, in.uv).rgb, 1.0);
+}